@@ -1,39 +1,143 @@
-use crate::{Solution, SolutionPair};
-use std::fs::read_to_string;
+use crate::Solution;
+use crate::etc::day::{Day, Reference};
+use crate::etc::error::{AocError, AocResult};
+use crate::etc::params::Params;
+use crate::etc::rng::Rng;
+use std::borrow::Cow;
+use std::cmp::Reverse;
+use std::collections::VecDeque;
+use std::io::BufRead;
+use std::sync::LazyLock;
 
-pub fn solve() -> SolutionPair {
+pub struct Day01;
 
-    let contents = read_to_string("input/day01_input.txt").expect("failed to parse input file");
+impl Day for Day01 {
+    type Input<'a> = Calibration<'a>;
 
-    let mut lines = contents.lines();
-    
-    let mut solution1 = 0;
-    let mut solution2 = 0;
+    fn name(&self) -> &'static str {
+        "Trebuchet?!"
+    }
 
-    while let Some(line) = lines.next() {
-        let result = get_outside_digits(&line);
-        let result_val = result.0 * 10 + result.1;
-        solution1 += result_val;
+    fn parse<'a>(&self, input: &'a str) -> AocResult<Self::Input<'a>> {
+        self.parse_with(input, &Params::new())
     }
 
-    let mut lines = contents.lines();
+    /// Takes the part 2 `vocabulary` (see `Vocabulary::parse`) and whether to
+    /// `ignore-case`.
+    fn parse_with<'a>(&self, input: &'a str, params: &Params) -> AocResult<Self::Input<'a>> {
+        let scanner = if params.is_empty() {
+            Cow::Borrowed(&*ENGLISH)
+        } else {
+            Cow::Owned(DigitScanner::new(&vocabulary_from(params)?))
+        };
+        let lines: Vec<&str> = input.lines().collect();
+
+        for (idx, line) in lines.iter().enumerate() {
+            if scanner.first(line).is_none() {
+                return Err(no_digit().at_line(idx + 1));
+            }
+        }
 
-    while let Some(line) = lines.next() {
-        let result = get_outside_digits_including_words(&line);
-        let result_val = result.0 * 10 + result.1;
-        solution2 += result_val;
+        Ok(Calibration { lines, scanner })
     }
 
-    (Solution::from(solution1), Solution::from(solution2))
+    fn part1(&self, input: &Self::Input<'_>) -> AocResult<Solution> {
+        let mut solution1 = 0;
+
+        for line in &input.lines {
+            let result = get_outside_digits(line);
+            let result_val = result.0 * 10 + result.1;
+            solution1 += result_val;
+        }
+
+        Ok(Solution::from(solution1))
+    }
+
+    fn part2(&self, input: &Self::Input<'_>) -> AocResult<Solution> {
+        let mut solution2 = 0;
+
+        for line in &input.lines {
+            let result = input.scanner.outside_digits(line);
+            let result_val = result.0 * 10 + result.1;
+            solution2 += result_val;
+        }
+
+        Ok(Solution::from(solution2))
+    }
+
+    fn generate(&self, rng: &mut Rng, size: usize) -> Option<String> {
+        Some(generate_calibration(rng, size))
+    }
+
+    fn reference(&self) -> Option<Reference> {
+        Some(|input, params| {
+            let sums = vocabulary_from(params).and_then(|vocabulary| scan_every_position(input, &vocabulary))?;
+            Ok((Solution::from(sums.0), Solution::from(sums.1)))
+        })
+    }
 }
 
-fn get_outside_digits(line: &str) -> (u32, u32) {
+/// The calibration document's lines, and what part 2 looks for in them.
+pub struct Calibration<'a> {
+    pub lines: Vec<&'a str>,
+    pub scanner: Cow<'static, DigitScanner>,
+}
+
+/// The error for a line without a single digit, written or spelled out.
+/// Part 1 only counts written digits, and takes 0 for a line with none.
+fn no_digit() -> AocError {
+    AocError::new("no digit in the line")
+}
+
+/// Sums both parts' calibration values of a document read from `reader` one
+/// line at a time, so memory use is bounded by the longest line rather than
+/// the whole document.
+pub fn calibrate(mut reader: impl BufRead, scanner: &DigitScanner) -> AocResult<(u64, u64)> {
+    let mut sums = (0, 0);
+    let mut buffer = String::new();
+
+    for line_number in 1.. {
+        buffer.clear();
+
+        let read = reader.read_line(&mut buffer)
+            .map_err(|e| AocError::new(format!("failed to read: {}", e)).at_line(line_number))?;
+        if read == 0 {
+            break;
+        }
+
+        let line = buffer.trim_end_matches(['\n', '\r']);
+        if scanner.first(line).is_none() {
+            return Err(no_digit().at_line(line_number));
+        }
+
+        let (first, last) = get_outside_digits(line);
+        sums.0 += u64::from(first * 10 + last);
+        let (first, last) = scanner.outside_digits(line);
+        sums.1 += u64::from(first * 10 + last);
+    }
+
+    Ok(sums)
+}
+
+/// The vocabulary `params` ask for, English unless one is given.
+fn vocabulary_from(params: &Params) -> AocResult<Vocabulary> {
+    params.expect_only(&["vocabulary", "ignore-case"])?;
+
+    let mut vocabulary = match params.get("vocabulary") {
+        Some(text) => Vocabulary::parse(text).map_err(|e| AocError::new(format!("in the vocabulary, {}", e)))?,
+        None => Vocabulary::english(),
+    };
+    vocabulary.ignore_case = params.flag("ignore-case", false)?;
+
+    Ok(vocabulary)
+}
+
+/// The first and last digit in the line, 0 for each if it has none.
+pub fn get_outside_digits(line: &str) -> (u32, u32) {
     let mut first_digit = 0;
     let mut last_digit = 0;
 
-    let mut chars = line.chars();
-
-    while let Some(c) = chars.next() {
+    for c in line.chars() {
         match c.to_digit(10) {
             Some(val) => {
                 first_digit = val;
@@ -43,9 +147,7 @@ fn get_outside_digits(line: &str) -> (u32, u32) {
         }
     }
 
-    let mut chars = line.chars().rev();
-
-    while let Some(c) = chars.next() {
+    for c in line.chars().rev() {
         match c.to_digit(10) {
             Some(val) => {
                 last_digit = val;
@@ -58,75 +160,461 @@ fn get_outside_digits(line: &str) -> (u32, u32) {
     (first_digit, last_digit)
 }
 
-fn get_outside_digits_including_words(line: &str) -> (u32, u32) {
+/// Like `get_outside_digits`, but spelled-out digits count too.
+pub fn get_outside_digits_including_words(line: &str) -> (u32, u32) {
+    ENGLISH.outside_digits(line)
+}
+
+/// The scanner for the digits and their English words, built on first use.
+static ENGLISH: LazyLock<DigitScanner> = LazyLock::new(|| DigitScanner::new(&Vocabulary::english()));
+
+/// The spelled-out digits that count in part 2, with their values.
+pub const DIGIT_WORDS: [(&str, u32); 10] = [
+    ("zero", 0), ("one", 1), ("two", 2), ("three", 3), ("four", 4),
+    ("five", 5), ("six", 6), ("seven", 7), ("eight", 8), ("nine", 9),
+];
+
+/// The words that count in part 2 besides the digits themselves. A word
+/// stands for its value written out, so with `dozen = 12` a line that starts
+/// with "dozen" starts with a 1 and one that ends with it ends with a 2.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Vocabulary {
+    pub words: Vec<(String, u32)>,
+    /// Whether words match regardless of case.
+    pub ignore_case: bool,
+}
+
+impl Vocabulary {
+    /// English zero to nine, as in the puzzle.
+    pub fn english() -> Self {
+        Vocabulary::from_words(&DIGIT_WORDS)
+    }
+
+    pub fn from_words(words: &[(&str, u32)]) -> Self {
+        let words = words.iter().map(|&(word, value)| (word.to_string(), value)).collect();
+        Vocabulary { words, ignore_case: false }
+    }
+
+    /// Reads a vocabulary with a `word = value` on every line. Blank lines
+    /// and lines starting with `#` are skipped.
+    pub fn parse(text: &str) -> AocResult<Self> {
+        let mut words = Vec::new();
+
+        for (idx, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
 
-    let mut new_line = String::from(line);
-    let numbers = vec!["zero","one","two","three","four","five","six","seven","eight","nine"];
-    let chars = vec!['0', '1', '2', '3', '4', '5', '6', '7', '8', '9'];
+            let error = |message: String| AocError::new(message).at_line(idx + 1);
 
-    for i in 0..numbers.len() {        
-        match new_line.find(numbers[i]) {
-            Some(idx) => new_line.insert(idx + 1, chars[i]),
-            _ => (),
+            let (word, value) = line.split_once('=')
+                .ok_or_else(|| error(String::from("expected '<word> = <value>'")))?;
+            let (word, value) = (word.trim(), value.trim());
+
+            if word.is_empty() {
+                return Err(error(String::from("expected a word before '='")));
+            }
+            let value = value.parse().map_err(|_| error(format!("not a number: '{}'", value)))?;
+
+            words.push((word.to_string(), value));
         }
-        match new_line.rfind(numbers[i]) {
-            Some(idx) => new_line.insert(idx + 1, chars[i]),
-            _ => (),
+
+        Ok(Vocabulary { words, ignore_case: false })
+    }
+}
+
+impl Default for Vocabulary {
+    fn default() -> Self {
+        Vocabulary::english()
+    }
+}
+
+/// The first digit of `value` written out.
+fn leading_digit(mut value: u32) -> u32 {
+    while value >= 10 {
+        value /= 10;
+    }
+    value
+}
+
+/// Finds the first and last digit or word of a vocabulary in a line, in one
+/// forward and one backward pass. Words may overlap: `eightwo` starts with an
+/// 8 and ends with a 2, as each pass only looks for its own end's word.
+#[derive(Clone, Debug)]
+pub struct DigitScanner {
+    forward: Automaton,
+    /// Built from the reversed words, to scan the line from its end.
+    backward: Automaton,
+    ignore_case: bool,
+}
+
+impl DigitScanner {
+    /// A scanner for the digits `0` to `9` and the vocabulary's words.
+    pub fn new(vocabulary: &Vocabulary) -> Self {
+        let ignore_case = vocabulary.ignore_case;
+
+        let digits: Vec<(Vec<u8>, u32)> = (0..10).map(|digit| (vec![b'0' + digit as u8], digit)).collect();
+        let words: Vec<(Vec<u8>, u32)> = vocabulary.words.iter()
+            .filter(|(word, _)| !word.is_empty())
+            .map(|(word, value)| (fold(word, ignore_case).as_bytes().to_vec(), *value))
+            .collect();
+
+        let forward: Vec<(Vec<u8>, u32)> = digits.into_iter().chain(words).collect();
+        let backward: Vec<(Vec<u8>, u32)> = forward.iter()
+            .map(|(word, value)| (word.iter().rev().copied().collect(), *value))
+            .collect();
+
+        DigitScanner { forward: Automaton::new(&forward), backward: Automaton::new(&backward), ignore_case }
+    }
+
+    /// The value of the digit or word that starts first.
+    pub fn first(&self, line: &str) -> Option<u32> {
+        self.forward.earliest(fold(line, self.ignore_case).bytes())
+    }
+
+    /// The value of the digit or word that ends last.
+    pub fn last(&self, line: &str) -> Option<u32> {
+        self.backward.earliest(fold(line, self.ignore_case).bytes().rev())
+    }
+
+    /// The first digit of the first digit or word and the last digit of the
+    /// last one, 0 for each if the line has neither.
+    pub fn outside_digits(&self, line: &str) -> (u32, u32) {
+        let line = fold(line, self.ignore_case);
+        let first = self.forward.earliest(line.bytes()).map_or(0, leading_digit);
+        let last = self.backward.earliest(line.bytes().rev()).map_or(0, |value| value % 10);
+
+        (first, last)
+    }
+}
+
+/// `text` in lower case if case is ignored.
+fn fold(text: &str, ignore_case: bool) -> Cow<'_, str> {
+    if ignore_case {
+        Cow::Owned(text.to_lowercase())
+    } else {
+        Cow::Borrowed(text)
+    }
+}
+
+/// An Aho-Corasick automaton over bytes, following every word at once.
+/// Bytes that no word uses share one class, so a state only needs a
+/// transition per byte that matters.
+#[derive(Clone, Debug)]
+struct Automaton {
+    /// The class of each byte. Bytes in none of the words are class 0.
+    classes: [u16; 256],
+    /// The number of classes, and so of transitions per state.
+    stride: usize,
+    /// The state after each class of byte, `stride` entries per state.
+    /// State 0 is the start.
+    next: Vec<u32>,
+    /// The length and value of the longest word ending in each state.
+    output: Vec<Option<(usize, u32)>>,
+    /// The length of the longest word.
+    longest: usize,
+}
+
+impl Automaton {
+    fn new(words: &[(Vec<u8>, u32)]) -> Self {
+        let mut classes = [0; 256];
+        let mut stride = 1;
+
+        for &byte in words.iter().flat_map(|(word, _)| word) {
+            if classes[byte as usize] == 0 {
+                classes[byte as usize] = stride as u16;
+                stride += 1;
+            }
         }
+
+        // A trie of the words first, then the links for every other class.
+        let mut next = vec![0; stride];
+        let mut output = vec![None];
+        let mut is_child = vec![false; stride];
+
+        for (word, value) in words {
+            let mut state = 0;
+
+            for &byte in word {
+                let edge = state * stride + classes[byte as usize] as usize;
+
+                if !is_child[edge] {
+                    is_child[edge] = true;
+                    next[edge] = output.len() as u32;
+                    next.resize(next.len() + stride, 0);
+                    is_child.resize(is_child.len() + stride, false);
+                    output.push(None);
+                }
+                state = next[edge] as usize;
+            }
+
+            // The first of two identical words wins.
+            output[state].get_or_insert((word.len(), *value));
+        }
+
+        // Breadth first, so the state a mismatch falls back to is complete
+        // before the states that fall back to it.
+        let mut fallback = vec![0; output.len()];
+        let mut queue: VecDeque<usize> = (0..stride)
+            .filter(|&class| is_child[class])
+            .map(|class| next[class] as usize)
+            .collect();
+
+        while let Some(state) = queue.pop_front() {
+            if output[state].is_none() {
+                output[state] = output[fallback[state]];
+            }
+
+            for class in 0..stride {
+                let edge = state * stride + class;
+
+                if is_child[edge] {
+                    let child = next[edge] as usize;
+                    fallback[child] = if state == 0 { 0 } else { next[fallback[state] * stride + class] as usize };
+                    queue.push_back(child);
+                } else {
+                    next[edge] = next[fallback[state] * stride + class];
+                }
+            }
+        }
+
+        let longest = words.iter().map(|(word, _)| word.len()).max().unwrap_or(0);
+
+        Automaton { classes, stride, next, output, longest }
     }
 
-    get_outside_digits(&new_line)
+    /// The value of the word that starts first in `bytes`, the longest one if
+    /// several start there.
+    fn earliest(&self, bytes: impl Iterator<Item = u8>) -> Option<u32> {
+        let mut state = 0;
+        let mut best: Option<(usize, u32)> = None;
+
+        for (pos, byte) in bytes.enumerate() {
+            // Anything found from here on would start later.
+            if best.is_some_and(|(start, _)| pos >= start + self.longest) {
+                break;
+            }
+
+            state = self.next[state * self.stride + self.classes[byte as usize] as usize] as usize;
+
+            if let Some((len, value)) = self.output[state] {
+                let start = pos + 1 - len;
+                if best.is_none_or(|(best_start, _)| start <= best_start) {
+                    best = Some((start, value));
+                }
+            }
+        }
+
+        best.map(|(_, value)| value)
+    }
+}
+
+/// Reference for both parts: looks for a digit and for every word of the
+/// vocabulary at every position of every line. Every line needs one.
+fn scan_every_position(document: &str, vocabulary: &Vocabulary) -> AocResult<(u32, u32)> {
+    let words: Vec<(String, u32)> = vocabulary.words.iter()
+        .filter(|(word, _)| !word.is_empty())
+        .map(|(word, value)| (fold(word, vocabulary.ignore_case).into_owned(), *value))
+        .collect();
+
+    let mut sums = (0, 0);
+
+    for (idx, line) in document.lines().enumerate() {
+        let line = fold(line, vocabulary.ignore_case);
+        let mut digits = Vec::new();
+        // Every digit and word found, as its start, end and value.
+        let mut found = Vec::new();
+
+        for start in 0..line.len() {
+            let rest = &line.as_bytes()[start..];
+
+            if rest[0].is_ascii_digit() {
+                digits.push(u32::from(rest[0] - b'0'));
+                found.push((start, start + 1, u32::from(rest[0] - b'0')));
+            }
+            for (word, value) in &words {
+                if rest.starts_with(word.as_bytes()) {
+                    found.push((start, start + word.len(), *value));
+                }
+            }
+        }
+
+        if let (Some(first), Some(last)) = (digits.first(), digits.last()) {
+            sums.0 += first * 10 + last;
+        }
+
+        // The longer word wins where two start, or end, at the same place.
+        let first = found.iter().min_by_key(|&&(start, end, _)| (start, Reverse(end)));
+        let last = found.iter().min_by_key(|&&(start, end, _)| (Reverse(end), start));
+
+        let (Some(&(_, _, first)), Some(&(_, _, last))) = (first, last) else {
+            return Err(no_digit().at_line(idx + 1));
+        };
+        sums.1 += leading_digit(first) * 10 + last % 10;
+    }
+
+    Ok(sums)
+}
+
+/// Calibration lines mixing letters, digits and spelled-out digits, each
+/// with at least one real digit.
+fn generate_calibration(rng: &mut Rng, lines: usize) -> String {
+    const WORDS: [&str; 9] = ["one", "two", "three", "four", "five", "six", "seven", "eight", "nine"];
+
+    let mut document = String::new();
+
+    for _ in 0..lines {
+        let pieces = rng.between(1, 6);
+        let digit_at = rng.below(pieces);
+
+        for piece in 0..pieces {
+            match rng.below(3) {
+                _ if piece == digit_at => document.push(char::from(b'1' + rng.below(9) as u8)),
+                0 => document.push_str(rng.choose::<&str>(&WORDS)),
+                1 => document.push(char::from(b'1' + rng.below(9) as u8)),
+                _ => {
+                    for _ in 0..rng.between(1, 4) {
+                        document.push(char::from(b'a' + rng.below(26) as u8));
+                    }
+                },
+            }
+        }
+
+        document.push('\n');
+    }
+
+    document
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    #[test]
-    fn example() {
-        let source = String::from("1abc2
+    crate::examples! {
+        Day01;
+        example: "1abc2
         pqr3stu8vwx
         a1b2c3d4e5f
-        treb7uchet");
-        let mut total: u32 = 0;
-        let mut lines = source.lines();
-        let answers = vec![12, 38, 15, 77];
-        let rolling_total = vec![12, 50, 65, 142];
-        let mut counter = 0;
-        
-        while let Some(line) = lines.next() {            
-            let result = get_outside_digits(&line);
-            let result_value = result.0 * 10 + result.1;
-            total = total + result_value;
-            assert_eq!(result_value, answers[counter]);
-            assert_eq!(total, rolling_total[counter]);
-            counter += 1;
-        }
-    }
-
-    #[test]
-    fn example_2() {
-
-        let source = String::from("two1nine
+        treb7uchet" => part1 = 142;
+        example_2: "two1nine
         eightwothree
         abcone2threexyz
         xtwone3four
         4nineeightseven2
         zoneight234
-        7pqrstsixteen");
-        let mut total: u32 = 0;
-        let mut lines = source.lines();
-        let answers = vec![29, 83, 13, 24, 42, 14, 76];
-        let rolling_total = vec![29, 112, 125, 149, 191, 205, 281];
-        let mut counter = 0;
-        
-        while let Some(line) = lines.next() {            
-            let result = get_outside_digits_including_words(&line);
-            let result_value = result.0 * 10 + result.1;
-            total = total + result_value;
-            assert_eq!(result_value, answers[counter]);
-            assert_eq!(total, rolling_total[counter]);
-            counter += 1;
+        7pqrstsixteen" => part2 = 281;
+        crlf: raw "1abc2\r\ntreb7uchet\r\n" => part1 = 89;
+    }
+
+    /// The implementation the scanner replaced, marking each word's first and
+    /// last occurrence by inserting its digit into the line.
+    fn inserting_digits(line: &str) -> (u32, u32) {
+        let mut new_line = String::from(line);
+        let numbers = ["zero","one","two","three","four","five","six","seven","eight","nine"];
+        let chars = ['0', '1', '2', '3', '4', '5', '6', '7', '8', '9'];
+
+        for (number, c) in numbers.iter().zip(chars) {
+            if let Some(idx) = new_line.find(number) {
+                new_line.insert(idx + 1, c);
+            }
+            if let Some(idx) = new_line.rfind(number) {
+                new_line.insert(idx + 1, c);
+            }
+        }
+
+        get_outside_digits(&new_line)
+    }
+
+    #[test]
+    fn scanning() {
+        let scanner = DigitScanner::new(&Vocabulary::english());
+        let example_2 = [
+            ("two1nine", (2, 9)), ("eightwothree", (8, 3)), ("abcone2threexyz", (1, 3)), ("xtwone3four", (2, 4)),
+            ("4nineeightseven2", (4, 2)), ("zoneight234", (1, 4)), ("7pqrstsixteen", (7, 6)),
+        ];
+
+        for (line, digits) in example_2 {
+            assert_eq!(scanner.outside_digits(line), digits, "{}", line);
+        }
+        assert_eq!(scanner.outside_digits("eightwo"), (8, 2));
+        assert_eq!(scanner.outside_digits("oneight"), (1, 8));
+        assert_eq!(scanner.outside_digits("zero"), (0, 0));
+        assert_eq!(scanner.first("abc"), None);
+        assert_eq!(scanner.last("nineeigh"), Some(9));
+    }
+
+    #[test]
+    fn custom_vocabulary() {
+        let scanner = DigitScanner::new(&Vocabulary::from_words(&[("eight", 8), ("eighteen", 18), ("teen", 13)]));
+
+        // The longest word wins where several start, or end, at the same place.
+        assert_eq!(scanner.first("xeighteen"), Some(18));
+        assert_eq!(scanner.last("xeighteen"), Some(18));
+        assert_eq!(scanner.last("eighteenteen"), Some(13));
+        assert_eq!(scanner.outside_digits("one2eightx"), (2, 8));
+    }
+
+    #[test]
+    fn vocabularies() {
+        let vocabulary = Vocabulary::parse("# Italian\n  uno = 1\n\ndue=2\ndodici = 12\n").unwrap();
+        assert_eq!(vocabulary, Vocabulary::from_words(&[("uno", 1), ("due", 2), ("dodici", 12)]));
+
+        let scanner = DigitScanner::new(&vocabulary);
+        assert_eq!(scanner.outside_digits("xdodicix"), (1, 2));
+        assert_eq!(scanner.outside_digits("dueone"), (2, 2));
+        assert_eq!(scanner.outside_digits("Uno"), (0, 0));
+
+        let scanner = DigitScanner::new(&Vocabulary { ignore_case: true, ..vocabulary });
+        assert_eq!(scanner.outside_digits("Uno4DUE"), (1, 2));
+
+        assert_eq!(Vocabulary::parse("uno 1").unwrap_err().to_string(), "line 1: expected '<word> = <value>'");
+        assert_eq!(Vocabulary::parse("\n = 1").unwrap_err().to_string(), "line 2: expected a word before '='");
+        assert_eq!(Vocabulary::parse("uno = one").unwrap_err().to_string(), "line 1: not a number: 'one'");
+    }
+
+    #[test]
+    fn matches_reference() {
+        let mut vocabulary = Vocabulary::from_words(&[("eight", 8), ("eighteen", 18), ("teen", 13), ("ten", 10), ("n", 5)]);
+        vocabulary.ignore_case = true;
+        let scanner = DigitScanner::new(&vocabulary);
+        let pieces = ["eight", "EIGHT", "een", "teen", "te", "n", "x", "3"];
+        let mut rng = Rng::new(22);
+
+        for _ in 0..2000 {
+            let line: String = (0..rng.between(0, 6)).map(|_| *rng.choose(&pieces)).collect();
+            let (first, last) = scanner.outside_digits(&line);
+
+            match scan_every_position(&line, &vocabulary) {
+                Ok((_, sum)) => assert_eq!(sum, first * 10 + last, "{}", line),
+                Err(_) => assert_eq!(scanner.first(&line), None, "{}", line),
+            }
+        }
+    }
+
+    #[test]
+    fn streaming() {
+        let scanner = DigitScanner::new(&Vocabulary::english());
+        let document = "two1nine\r\neightwothree\nabcone2threexyz\nxtwone3four\n4nineeightseven2\nzoneight234\n7pqrstsixteen";
+
+        assert_eq!(calibrate(document.as_bytes(), &scanner), Ok((11 + 22 + 33 + 42 + 24 + 77, 281)));
+        assert_eq!(calibrate("".as_bytes(), &scanner), Ok((0, 0)));
+
+        let error = calibrate("1abc2\npqr3stu8vwx\nabcdef\n".as_bytes(), &scanner).unwrap_err();
+        assert_eq!(error.to_string(), "line 3: no digit in the line");
+        assert_eq!(Day01.parse("1abc2\n\n7").err().map(|e| e.to_string()).as_deref(), Some("line 2: no digit in the line"));
+    }
+
+    #[test]
+    fn matches_inserting_digits() {
+        let scanner = DigitScanner::new(&Vocabulary::english());
+        let pieces = ["zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+            "e", "n", "o", "t", "x", "1", "7"];
+        let mut rng = Rng::new(2023);
+
+        for _ in 0..5000 {
+            let line: String = (0..rng.between(0, 8)).map(|_| *rng.choose(&pieces)).collect();
+            assert_eq!(scanner.outside_digits(&line), inserting_digits(&line), "{}", line);
         }
     }
 }
\ No newline at end of file
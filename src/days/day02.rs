@@ -1,188 +1,522 @@
-use crate::{Solution, SolutionPair};
-use std::fs::read_to_string;
-use std::error::Error;
-use regex::Regex;
+use crate::Solution;
+use crate::etc::day::{Day, Reference};
+use crate::etc::error::{column_of, AocError, AocResult};
+use crate::etc::params::Params;
+use crate::etc::rng::Rng;
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::fmt::{self, Display, Formatter};
+use std::ops::Range;
+
+pub struct Day02;
+
+impl Day for Day02 {
+    type Input<'a> = Games<'a>;
+
+    fn name(&self) -> &'static str {
+        "Cube Conundrum"
+    }
+
+    fn parse<'a>(&self, input: &'a str) -> AocResult<Self::Input<'a>> {
+        self.parse_with(input, &Params::new())
+    }
+
+    /// Takes the `bag` to check the games against (see `parse_bag`), and
+    /// whether to be `strict` about colours that aren't in it: they're an
+    /// error unless `strict=false`, which makes them colours like any other.
+    fn parse_with<'a>(&self, input: &'a str, params: &Params) -> AocResult<Self::Input<'a>> {
+        let (bag, strict) = settings_from(params)?;
+        // Colours a game never shows still count for its power, as none.
+        let none = bag.emptied();
 
-pub fn solve() -> SolutionPair {
+        #[cfg(feature = "regex-parser")]
+        let parse_line = regex_parser::parse_line;
 
-    let contents = read_to_string("input/day02_input.txt").expect("failed to parse input file");
-    let game = Game { red: 12, green: 13, blue: 14 };
+        let mut games = Vec::new();
+
+        for (idx, line) in input.lines().enumerate() {
+            let (id, mut game) = parse_line(line).map_err(|e| e.at_line(idx + 1))?;
+
+            if let Some((colour, _)) = game.colours().find(|&(colour, _)| strict && !bag.contains(colour)) {
+                return Err(AocError::new(format!("unknown colour '{}'", colour)).at_line(idx + 1));
+            }
+
+            game.max(&none);
+            games.push((id, game));
+        }
 
-    let mut lines = contents.lines();
+        Ok(Games { games, bag })
+    }
 
-    let mut solution1 = 0;
-    let mut solution2 = 0;
+    fn part1(&self, input: &Self::Input<'_>) -> AocResult<Solution> {
+        Ok(Solution::from(calculate_id_sum(&input.games, &input.bag)))
+    }
 
-    while let Some(line) = lines.next() {
-        let result = calculate_id_sum(&line, &game).expect("Failed to parse games.");
-        solution1 += result;
+    fn part2(&self, input: &Self::Input<'_>) -> AocResult<Solution> {
+        calculate_power(&input.games).map(Solution::from)
     }
 
-    let mut lines = contents.lines();
-    while let Some(line) = lines.next() {
-        let result = calculate_power(&line).expect("Failed to parse games.");
-        solution2 += result;
+    fn generate(&self, rng: &mut Rng, size: usize) -> Option<String> {
+        Some(generate_games(rng, size))
     }
 
-    (Solution::from(solution1), Solution::from(solution2))
+    fn reference(&self) -> Option<Reference> {
+        Some(|input, params| {
+            let sums = settings_from(params).and_then(|(bag, strict)| replay_games(input, &bag, strict))?;
+            Ok((Solution::from(sums.0), Solution::from(sums.1)))
+        })
+    }
 }
 
+/// The cubes the Elf has in the bag, unless another bag is given.
+pub const BAG: [(&str, u32); 3] = [("red", 12), ("green", 13), ("blue", 14)];
+
+/// Each game's ID with the fewest cubes of each colour it needs, and the bag
+/// they're checked against.
 #[derive(Debug)]
-pub struct Game {
-    pub red: u32,
-    pub green: u32,
-    pub blue: u32,
+pub struct Games<'a> {
+    pub games: Vec<(u32, Game<'a>)>,
+    pub bag: Game<'static>,
+}
+
+/// The bag and strictness `params` ask for.
+fn settings_from(params: &Params) -> AocResult<(Game<'static>, bool)> {
+    params.expect_only(&["bag", "strict"])?;
+
+    let bag = match params.get("bag") {
+        Some(text) => parse_bag(text).map_err(|e| AocError::new(format!("in the bag, {}", e)))?,
+        None => Game::from(BAG),
+    };
+
+    Ok((bag, params.flag("strict", true)?))
 }
 
-impl Game {
-    fn max(&mut self, other: &Self) {
-        self.red = std::cmp::max(self.red, other.red);
-        self.blue = std::cmp::max(self.blue, other.blue);
-        self.green = std::cmp::max(self.green, other.green);
+/// A number of cubes of each colour, in the order the colours showed up. A
+/// colour that isn't there has no cubes.
+#[derive(Clone, Debug, Default)]
+pub struct Game<'a> {
+    cubes: Vec<(Cow<'a, str>, u32)>,
+}
+
+impl<'a> Game<'a> {
+    pub fn new() -> Self {
+        Game::default()
+    }
+
+    /// The number of cubes of `colour`.
+    pub fn get(&self, colour: &str) -> u32 {
+        self.colours().find(|&(other, _)| other == colour).map_or(0, |(_, count)| count)
     }
 
-    fn can_fit(&self, other: &Self) -> bool {
-        self.red >= other.red &&
-        self.blue >= other.blue &&
-        self.green >= other.green
+    pub fn contains(&self, colour: &str) -> bool {
+        self.colours().any(|(other, _)| other == colour)
+    }
+
+    /// Sets the number of cubes of `colour`.
+    pub fn set(&mut self, colour: impl Into<Cow<'a, str>>, count: u32) {
+        let colour = colour.into();
+
+        match self.cubes.iter_mut().find(|(other, _)| *other == colour) {
+            Some(cubes) => cubes.1 = count,
+            None => self.cubes.push((colour, count)),
+        }
+    }
+
+    /// Every colour with its number of cubes.
+    pub fn colours(&self) -> impl Iterator<Item = (&str, u32)> {
+        self.cubes.iter().map(|(colour, count)| (colour.as_ref(), *count))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cubes.is_empty()
+    }
+
+    /// The same colours without any cubes.
+    pub fn emptied(&self) -> Self {
+        Game { cubes: self.cubes.iter().map(|(colour, _)| (colour.clone(), 0)).collect() }
+    }
+
+    /// Raises `colour` to at least `count` cubes.
+    pub fn raise(&mut self, colour: impl Into<Cow<'a, str>>, count: u32) {
+        let colour = colour.into();
+
+        match self.cubes.iter_mut().find(|(other, _)| *other == colour) {
+            Some(cubes) => cubes.1 = cubes.1.max(count),
+            None => self.cubes.push((colour, count)),
+        }
+    }
+
+    /// Raises each colour to at least `other`'s count.
+    pub fn max(&mut self, other: &Game<'a>) {
+        for (colour, count) in &other.cubes {
+            match self.cubes.iter_mut().find(|(own, _)| own == colour) {
+                Some(cubes) => cubes.1 = cubes.1.max(*count),
+                None => self.cubes.push((colour.clone(), *count)),
+            }
+        }
+    }
+
+    /// Whether `other` could have been drawn from these cubes.
+    pub fn can_fit(&self, other: &Game) -> bool {
+        other.colours().all(|(colour, count)| count <= self.get(colour))
+    }
+
+    /// The number of cubes of every colour multiplied together, 0 if there
+    /// are no colours at all, or None if that doesn't fit in a `u64`.
+    pub fn power(&self) -> Option<u64> {
+        match self.is_empty() || self.colours().any(|(_, count)| count == 0) {
+            true => Some(0),
+            false => self.colours().try_fold(1u64, |power, (_, count)| power.checked_mul(count.into())),
+        }
+    }
+}
+
+impl<'a, S: Into<Cow<'a, str>>, const N: usize> From<[(S, u32); N]> for Game<'a> {
+    fn from(cubes: [(S, u32); N]) -> Self {
+        let mut game = Game::new();
+        for (colour, count) in cubes {
+            game.set(colour, count);
+        }
+        game
     }
 }
 
-impl PartialEq for Game {
+impl PartialEq for Game<'_> {
     fn eq(&self, other: &Self) -> bool {
-        self.red == other.red &&
-        self.green == other.green &&
-        self.blue == other.blue
+        self.colours().all(|(colour, count)| other.get(colour) == count) &&
+        other.colours().all(|(colour, count)| self.get(colour) == count)
     }
 }
 
-fn calculate_id_sum(data: &str, game: &Game) -> Result<u32, Box<dyn Error>> {
+impl Display for Game<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let cubes: Vec<String> = self.colours().map(|(colour, count)| format!("{} {}", count, colour)).collect();
+        write!(f, "{}", cubes.join(", "))
+    }
+}
+
+/// Sums the IDs of the games that can be contained in max_game.
+pub fn calculate_id_sum(games: &[(u32, Game)], max_game: &Game) -> u64 {
+    games.iter()
+        .filter(|(_, game)| max_game.can_fit(game))
+        .map(|&(id, _)| u64::from(id))
+        .sum()
+}
 
-    let mut total: u32 = 0;
+/// Sums the power of each game's fewest cubes, failing if a power or the sum
+/// doesn't fit in a `u64`.
+pub fn calculate_power(games: &[(u32, Game)]) -> AocResult<u64> {
+    games.iter().try_fold(0u64, |total, (id, game)| {
+        let power = game.power()
+            .ok_or_else(|| AocError::new(format!("the power of game {} is too large", id)))?;
+        total.checked_add(power)
+            .ok_or_else(|| AocError::new("the sum of the powers is too large"))
+    })
+}
+
+/// Reads a bag like `12 red, 13 green, 14 blue`, the colours separated by
+/// commas or new lines.
+pub fn parse_bag(text: &str) -> AocResult<Game<'static>> {
+    let mut bag = Game::new();
 
-    for datam in data.lines() {
-        let result = parse_line(&datam, game)?;
-        total = total + result;
+    for cubes in text.split([',', '\n']).map(str::trim).filter(|cubes| !cubes.is_empty()) {
+        let (count, colour) = cubes.split_once(' ')
+            .ok_or_else(|| AocError::new(format!("expected '<count> <colour>', not '{}'", cubes)))?;
+        let colour = colour.trim();
+        let count = count.parse()
+            .map_err(|_| AocError::new(format!("invalid {} cube count '{}'", colour, count)))?;
+
+        bag.set(colour.to_string(), count);
     }
-    
-    Ok(total)
+
+    if bag.is_empty() {
+        return Err(AocError::new("no cubes in the bag"));
+    }
+
+    Ok(bag)
 }
 
-fn calculate_power(data: &str) -> Result<u32, Box<dyn Error>> {
-    let mut total: u32 = 0;
+/// A game the way its line tells it: the ID and every round, along with
+/// where each of them is in the line.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GameRecord<'a> {
+    pub id: u32,
+    pub rounds: Vec<Game<'a>>,
+    /// The byte range of the ID in the line.
+    pub id_span: Range<usize>,
+    /// The byte range of each round in the line, in the order of `rounds`.
+    pub round_spans: Vec<Range<usize>>,
+}
 
-    for datam in data.lines() {
-        let result = parse_round(datam)?;
-        let power: u32 = &result.red * &result.green * &result.blue;
-        total = total + power;
+impl<'a> GameRecord<'a> {
+    /// The fewest cubes of each colour the rounds need.
+    pub fn fewest(&self) -> Game<'a> {
+        let mut fewest = Game::new();
+        for round in &self.rounds {
+            fewest.max(round);
+        }
+        fewest
     }
+}
 
-    Ok(total)
+/// A piece of a game line: the start of a round, with where it is in the
+/// line, or cubes drawn in that round.
+enum Token<'a> {
+    Round(Range<usize>),
+    Cubes(&'a str, u32),
 }
 
-/// Returns the Game's ID if it can be contained in max_game.
-/// Returns 0 otherwise
-fn parse_line(data: &str, max_game: &Game) -> Result<u32, Box<dyn Error>> {
-    let regex = Regex::new(r"(?m)^Game (\d+): (.*)$")?;
+/// Reads a line into a `GameRecord`, borrowing the colours from it.
+pub fn parse_record(line: &str) -> AocResult<GameRecord<'_>> {
+    let mut rounds: Vec<Game> = Vec::new();
+    let mut round_spans = Vec::new();
 
-    let result = regex.captures_iter(data).next().unwrap();
-    let id = result.get(1).unwrap().as_str().parse().unwrap_or(0);
-    let game = parse_round(result.get(2).unwrap().as_str())?;
+    let (id, id_span) = tokenize(line, |token| match token {
+        Token::Round(span) => {
+            rounds.push(Game::new());
+            round_spans.push(span);
+        },
+        Token::Cubes(colour, count) => rounds.last_mut().expect("cubes come after their round").raise(colour, count),
+    })?;
 
-    if max_game.can_fit(&game) {
-        Ok(id)
-    }
-    else {
-        Ok(0)
-    }
+    Ok(GameRecord { id, rounds, id_span, round_spans })
 }
 
-fn parse_round(data: &str) -> Result<Game, Box<dyn Error>> {
-    let regex = Regex::new(r"(?m)(.*?;)|(.+?)$")?;
+/// Returns the Game's ID and the fewest cubes of each colour it needs. The
+/// rounds aren't kept, the cubes go straight into the fewest.
+pub fn parse_line(line: &str) -> AocResult<(u32, Game<'_>)> {
+    let mut fewest = Game::new();
 
-    let mut ret_game = Game { red: 0, blue: 0, green: 0 };
+    let (id, _) = tokenize(line, |token| {
+        if let Token::Cubes(colour, count) = token {
+            fewest.raise(colour, count);
+        }
+    })?;
 
-    let result = regex.captures_iter(data);
+    Ok((id, fewest))
+}
 
-    for mats in result {
-        if let Some(mat) = mats.get(0) {
-            let game = parse_game(mat.as_str())?;
-            ret_game.max(&game);
+/// Walks `Game <id>: <rounds>`, the rounds separated by semicolons and the
+/// cubes in each by commas, handing every round and draw to `visit` in
+/// order. Returns the ID and where it is in the line.
+fn tokenize<'a>(line: &'a str, mut visit: impl FnMut(Token<'a>)) -> AocResult<(u32, Range<usize>)> {
+    let (id, rounds) = line.strip_prefix("Game ")
+        .and_then(|rest| rest.split_once(':'))
+        .ok_or_else(|| AocError::new("expected 'Game <id>: <rounds>'"))?;
+    let number = id.parse()
+        .map_err(|_| AocError::new(format!("invalid game id '{}'", id)).at_column(column_of(line, id)))?;
+
+    for round in rounds.split(';') {
+        visit(Token::Round(span_of(line, round.trim())));
+
+        for draw in round.split(',').map(str::trim) {
+            let (count, colour) = draw.split_once(' ')
+                .map(|(count, colour)| (count, colour.trim_start()))
+                .filter(|(_, colour)| !colour.is_empty())
+                .ok_or_else(|| {
+                    AocError::new(format!("expected '<count> <colour>', not '{}'", draw)).at_column(column_of(line, draw))
+                })?;
+            let count = count.parse().map_err(|_| {
+                AocError::new(format!("invalid {} cube count '{}'", colour, count)).at_column(column_of(line, count))
+            })?;
+
+            visit(Token::Cubes(colour, count));
         }
     }
 
-    Ok(ret_game)
+    Ok((number, span_of(line, id)))
+}
+
+/// The byte range `part` takes up in `line`, where `part` is a slice of it.
+fn span_of(line: &str, part: &str) -> Range<usize> {
+    let start = column_of(line, part) - 1;
+    start..start + part.len()
 }
 
-fn parse_game(data: &str) -> Result<Game, Box<dyn Error>> {
-    let regex = Regex::new(r"(?m)(\d+) (red|blue|green)")?;
+/// The regex parser `parse_line` replaced, its expressions now compiled once.
+/// Kept to benchmark against: built with `--features regex-parser`, the day
+/// parses with it instead.
+#[cfg(feature = "regex-parser")]
+pub mod regex_parser {
+    use super::Game;
+    use crate::etc::error::{AocError, AocResult};
+    use regex::Regex;
+    use std::sync::LazyLock;
+
+    static LINE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?m)^Game (\d+): (.*)$").unwrap());
+    static ROUND: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?m)(.*?;)|(.+?)$").unwrap());
+    static CUBES: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"(?m)(\d+) ([^\s,;]+)").unwrap());
+
+    /// Returns the Game's ID and the fewest cubes of each colour it needs.
+    pub fn parse_line(data: &str) -> AocResult<(u32, Game<'_>)> {
+        let result = LINE.captures_iter(data).next()
+            .ok_or_else(|| AocError::new("expected 'Game <id>: <rounds>'"))?;
+        let id = result.get(1).unwrap().as_str();
+        let id = id.parse()
+            .map_err(|_| AocError::new(format!("invalid game id '{}'", id)))?;
+        let game = parse_round(result.get(2).unwrap().as_str())?;
+
+        Ok((id, game))
+    }
+
+    fn parse_round(data: &str) -> AocResult<Game<'_>> {
+        let mut ret_game = Game::new();
+
+        for mats in ROUND.captures_iter(data) {
+            if let Some(mat) = mats.get(0) {
+                let game = parse_game(mat.as_str())?;
+                ret_game.max(&game);
+            }
+        }
 
-    let mut ret_game = Game { red: 0, blue: 0, green: 0 };
+        Ok(ret_game)
+    }
 
-    let results: Vec<(&str, &str)> = regex.captures_iter(data).map(|caps| {
-        let (_, [count, color]) = caps.extract();
-        (count, color)
-    }).collect();
+    fn parse_game(data: &str) -> AocResult<Game<'_>> {
+        let mut ret_game = Game::new();
 
-    let results_iter = results.iter();
+        for caps in CUBES.captures_iter(data) {
+            let (_, [count, color]) = caps.extract();
+            let count = count.parse()
+                .map_err(|_| AocError::new(format!("invalid {} cube count '{}'", color, count)))?;
 
-    for item in results_iter {
-        if item.1.eq("blue") {
-            ret_game.blue = item.0.parse().unwrap_or(0);
+            ret_game.set(color, count);
         }
 
-        if item.1.eq("green") {
-            ret_game.green = item.0.parse().unwrap_or(0);
+        Ok(ret_game)
+    }
+}
+
+/// Reference for both parts: checks every single draw against the bag, and
+/// multiplies the largest draw of each colour, the bag's included.
+fn replay_games(document: &str, bag: &Game, strict: bool) -> AocResult<(u64, u64)> {
+    let mut sums = (0, 0u64);
+
+    for (idx, line) in document.lines().enumerate() {
+        let error = |message: &str| AocError::new(message).at_line(idx + 1);
+
+        let (game, rounds) = line.split_once(": ")
+            .ok_or_else(|| error("expected 'Game <id>: <rounds>'"))?;
+        let id: u32 = game.strip_prefix("Game ")
+            .and_then(|id| id.parse().ok())
+            .ok_or_else(|| error("invalid game id"))?;
+
+        let mut possible = true;
+        let mut fewest: BTreeMap<&str, u32> = bag.colours().map(|(colour, _)| (colour, 0)).collect();
+
+        for draw in rounds.split(';').flat_map(|round| round.split(',')) {
+            let (count, colour) = draw.trim().split_once(' ')
+                .ok_or_else(|| error("expected '<count> <colour>'"))?;
+            let count: u32 = count.parse().map_err(|_| error("invalid cube count"))?;
+
+            if strict && !bag.contains(colour) {
+                return Err(error("unknown colour"));
+            }
+
+            possible &= count <= bag.get(colour);
+            let most = fewest.entry(colour).or_insert(0);
+            *most = (*most).max(count);
         }
 
-        if item.1.eq("red") {
-            ret_game.red = item.0.parse().unwrap_or(0);
+        if possible {
+            sums.0 += u64::from(id);
         }
+        let power = match fewest.values().any(|&count| count == 0) {
+            true => Some(0),
+            false => fewest.values().try_fold(1u64, |power, &count| power.checked_mul(count.into())),
+        };
+        sums.1 = power
+            .and_then(|power| sums.1.checked_add(power))
+            .ok_or_else(|| error("power too large"))?;
     }
 
-    Ok(ret_game)
+    Ok(sums)
 }
 
+/// Games of one to six rounds, each showing up to 20 cubes of some colours.
+fn generate_games(rng: &mut Rng, games: usize) -> String {
+    let mut document = String::new();
+
+    for id in 1..=games {
+        let mut rounds = Vec::new();
+
+        for _ in 0..rng.between(1, 6) {
+            let mut colours = ["red", "green", "blue"];
+            rng.shuffle(&mut colours);
+
+            let mut cubes = Vec::new();
+            for colour in &colours[..rng.between(1, 3) as usize] {
+                cubes.push(format!("{} {}", rng.between(1, 20), colour));
+            }
+            rounds.push(cubes.join(", "));
+        }
+
+        document.push_str(&format!("Game {}: {}\n", id, rounds.join("; ")));
+    }
+
+    document
+}
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    crate::examples! {
+        Day02;
+        example: "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
+Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
+Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
+Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
+Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green" => part1 = 8, part2 = 2286;
+    }
+
     #[test]
     fn parse_game_test() {
-        let test1 = String::from(" 3 blue, 4 red;");
-        let test2 = String::from(" 1 red, 2 green, 6 blue;");
-        let test3 = String::from(" 2 green");
-
-        let result1 = parse_game(&test1).expect("Game did not parse correctly");
-        let result2 = parse_game(&test2).expect("Game did not parse correctly");
-        let result3 = parse_game(&test3).expect("Game did not parse correctly");
+        let record = parse_record("Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green")
+            .expect("Game did not parse correctly");
+
+        assert_eq!(record.id, 1);
+        assert_eq!(record.rounds, vec![
+            Game::from([("red", 4), ("green", 0), ("blue", 3)]),
+            Game::from([("red", 1), ("green", 2), ("blue", 6)]),
+            Game::from([("red", 0), ("green", 2), ("blue", 0)]),
+        ]);
+        assert_eq!(record.fewest(), Game::from([("red", 4), ("green", 2), ("blue", 6)]));
+    }
 
-        assert_eq!(result1, Game { red: 4, green: 0, blue: 3});
-        assert_eq!(result2, Game { red: 1, green: 2, blue: 6});
-        assert_eq!(result3, Game { red: 0, green: 2, blue: 0});
+    #[test]
+    fn spans() {
+        let line = "Game 12: 3 blue, 4 red;1 red ; 2 green";
+        let record = parse_record(line).unwrap();
+
+        assert_eq!(&line[record.id_span.clone()], "12");
+        let rounds: Vec<&str> = record.round_spans.iter().map(|span| &line[span.clone()]).collect();
+        assert_eq!(rounds, vec!["3 blue, 4 red", "1 red", "2 green"]);
+        // The colours are borrowed from the line.
+        assert!(record.rounds[0].colours().all(|(colour, _)| line.contains(colour)));
     }
 
     #[test]
     fn parse_round_test() {
-        let source = String::from(" 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
-         1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
-         8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
-         1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
-         6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green");
+        let source = String::from("Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
+Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
+Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
+Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
+Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green");
 
          let mut tests = source.lines();
+         let parse_round = |line| parse_line(line).map(|(_, game)| game);
 
          let result = parse_round(tests.next().unwrap()).expect("Round did not parse correctly");
-         assert_eq!(Game { red: 4, blue: 6, green: 2 }, result);
+         assert_eq!(Game::from([("red", 4), ("blue", 6), ("green", 2)]), result);
          let result = parse_round(tests.next().unwrap()).expect("Round did not parse correctly");
-         assert_eq!(Game { red: 1, blue: 4, green: 3 }, result);
+         assert_eq!(Game::from([("red", 1), ("blue", 4), ("green", 3)]), result);
          let result = parse_round(tests.next().unwrap()).expect("Round did not parse correctly");
-         assert_eq!(Game { red: 20, blue: 6, green: 13 }, result);
+         assert_eq!(Game::from([("red", 20), ("blue", 6), ("green", 13)]), result);
          let result = parse_round(tests.next().unwrap()).expect("Round did not parse correctly");
-         assert_eq!(Game { red: 14, blue: 15, green: 3 }, result);
+         assert_eq!(Game::from([("red", 14), ("blue", 15), ("green", 3)]), result);
          let result = parse_round(tests.next().unwrap()).expect("Round did not parse correctly");
-         assert_eq!(Game { red: 6, blue: 2, green: 3 }, result);
+         assert_eq!(Game::from([("red", 6), ("blue", 2), ("green", 3)]), result);
     }
 
     #[test]
@@ -195,22 +529,22 @@ Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green");
 
          let mut tests = source.lines();
          let mut total: u32 = 0;
-         let max_game = Game { red: 12, green: 13, blue: 14 };
+         let max_game = Game::from([("red", 12), ("green", 13), ("blue", 14)]);
 
-         let result = parse_line(tests.next().unwrap(), &max_game).expect("Round did not parse correctly");
-         total = total + result;
+         let (id, game) = parse_line(tests.next().unwrap()).expect("Round did not parse correctly");
+         total += if max_game.can_fit(&game) { id } else { 0 };
          assert_eq!(total, 1);
-         let result = parse_line(tests.next().unwrap(), &max_game).expect("Round did not parse correctly");
-         total = total + result;
+         let (id, game) = parse_line(tests.next().unwrap()).expect("Round did not parse correctly");
+         total += if max_game.can_fit(&game) { id } else { 0 };
          assert_eq!(total, 3);
-         let result = parse_line(tests.next().unwrap(), &max_game).expect("Round did not parse correctly");
-         total = total + result;
+         let (id, game) = parse_line(tests.next().unwrap()).expect("Round did not parse correctly");
+         total += if max_game.can_fit(&game) { id } else { 0 };
          assert_eq!(total, 3);
-         let result = parse_line(tests.next().unwrap(), &max_game).expect("Round did not parse correctly");
-         total = total + result;
+         let (id, game) = parse_line(tests.next().unwrap()).expect("Round did not parse correctly");
+         total += if max_game.can_fit(&game) { id } else { 0 };
          assert_eq!(total, 3);
-         let result = parse_line(tests.next().unwrap(), &max_game).expect("Round did not parse correctly");
-         total = total + result;
+         let (id, game) = parse_line(tests.next().unwrap()).expect("Round did not parse correctly");
+         total += if max_game.can_fit(&game) { id } else { 0 };
          assert_eq!(total, 8);
     }
 
@@ -223,51 +557,132 @@ Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
 Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green");
 
          let mut tests = source.lines();
-         let mut total: u32 = 0;
+         let mut total: u64 = 0;
+         let parse_round = |line| parse_line(line).map(|(_, game)| game);
 
          let result = parse_round(tests.next().unwrap()).expect("Round did not parse correctly");
-         let power = &result.red * &result.green * &result.blue;
-         total = total + power;
+         let power = result.power().unwrap();
+         total += power;
          assert_eq!(power, 48);
          assert_eq!(total, 48);
          let result = parse_round(tests.next().unwrap()).expect("Round did not parse correctly");
-         let power = &result.red * &result.green * &result.blue;
-         total = total + power;
+         let power = result.power().unwrap();
+         total += power;
          assert_eq!(power, 12);
          assert_eq!(total, 60);
          let result = parse_round(tests.next().unwrap()).expect("Round did not parse correctly");
-         let power = &result.red * &result.green * &result.blue;
-         total = total + power;
+         let power = result.power().unwrap();
+         total += power;
          assert_eq!(power, 1560);
          assert_eq!(total, 1620);
          let result = parse_round(tests.next().unwrap()).expect("Round did not parse correctly");
-         let power = &result.red * &result.green * &result.blue;
-         total = total + power;
+         let power = result.power().unwrap();
+         total += power;
          assert_eq!(power, 630);
          assert_eq!(total, 2250);
          let result = parse_round(tests.next().unwrap()).expect("Round did not parse correctly");
-         let power = &result.red * &result.green * &result.blue;
-         total = total + power;
+         let power = result.power().unwrap();
+         total += power;
          assert_eq!(power, 36);
          assert_eq!(total, 2286);
     }
 
     #[test]
-    fn example() {
-        let example = String::from("Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
-Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
-Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
-Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
-Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green");
+    fn colours() {
+        let mut game = Game::from([("red", 3), ("teal", 2)]);
+        game.max(&Game::from([("teal", 5), ("blue", 1)]));
+
+        assert_eq!(game, Game::from([("blue", 1), ("red", 3), ("teal", 5), ("green", 0)]));
+        assert_eq!(game.to_string(), "3 red, 5 teal, 1 blue");
+        assert_eq!(game.power(), Some(15));
+        assert_eq!(Game::new().power(), Some(0));
+        assert!(Game::from([("teal", 5), ("red", 3), ("blue", 9)]).can_fit(&game));
+        assert!(!Game::from(BAG).can_fit(&game));
+        assert_eq!(parse_bag("12 red\n 2 teal,").unwrap(), Game::from([("red", 12), ("teal", 2)]));
+    }
 
-        let example_game = Game {
-            red: 12, 
-            green: 13,
-            blue: 14, 
-        };
+    #[test]
+    fn bags_and_strictness() {
+        let input = "Game 1: 3 teal, 4 red; 2 green\nGame 2: 1 blue";
+        let mut params = Params::new();
+
+        let error = Day02.parse_with(input, &params).err().unwrap();
+        assert_eq!(error.to_string(), "line 1: unknown colour 'teal'");
+
+        params.set("strict", "false");
+        let games = Day02.parse_with(input, &params).unwrap();
+        assert_eq!(Day02.part1(&games).unwrap(), Solution::from(2u32));
+        assert_eq!(Day02.part2(&games).unwrap(), Solution::from(0u32));
+
+        params.set("bag", "5 teal, 4 red, 2 green");
+        let games = Day02.parse_with(input, &params).unwrap();
+        assert_eq!(Day02.part1(&games).unwrap(), Solution::from(1u32));
+        // Game 2 only has blue cubes, none of the colours in the bag.
+        assert_eq!(Day02.part2(&games).unwrap(), Solution::from(24u32));
+
+        params.set("bag", "4 red, 1 blue, 3 teal, 2 green");
+        params.set("strict", "true");
+        let games = Day02.parse_with("Game 7: 3 teal, 4 red, 1 blue; 2 green", &params).unwrap();
+        assert_eq!(Day02.part1(&games).unwrap(), Solution::from(7u32));
+        assert_eq!(Day02.part2(&games).unwrap(), Solution::from(24u32));
+
+        params.set("bag", "4 red, lots blue");
+        let error = Day02.parse_with(input, &params).err().unwrap();
+        assert_eq!(error.to_string(), "in the bag, invalid blue cube count 'lots'");
+    }
+
+    #[test]
+    fn large_powers() {
+        let input = "Game 1: 100 a, 100 b, 100 c, 100 d, 100 e";
+        let mut params = Params::new();
+        params.set("strict", "false");
+
+        // None of the bag's colours are there, so the power is 0.
+        let games = Day02.parse_with(input, &params).unwrap();
+        assert_eq!(Day02.part2(&games).unwrap(), Solution::from(0u32));
+
+        params.set("bag", "1 a, 1 b, 1 c, 1 d, 1 e");
+        let games = Day02.parse_with(input, &params).unwrap();
+        assert_eq!(Day02.part2(&games).unwrap(), Solution::from(10_000_000_000u64));
+        assert_eq!(Day02.reference().unwrap()(input, &params).unwrap().1, Solution::from(10_000_000_000u64));
+
+        let colours: Vec<String> = (0..10).map(|n| format!("100000 c{}", n)).collect();
+        let input = format!("Game 1: 1 c0\nGame 2: {}", colours.join(", "));
+        params.set("bag", colours.join(", "));
+        let games = Day02.parse_with(&input, &params).unwrap();
+        assert_eq!(Day02.part2(&games).unwrap_err().to_string(), "the power of game 2 is too large");
+        assert_eq!(Day02.reference().unwrap()(&input, &params).unwrap_err().to_string(), "line 2: power too large");
+    }
 
-        let result = calculate_id_sum(example.as_str(), &example_game).expect("Failed to parse games");
+    #[test]
+    fn malformed_games() {
+        let error = Day02.parse("Game 1: 3 blue\nGame 2 3 blue").unwrap_err();
+        assert_eq!(error.to_string(), "line 2: expected 'Game <id>: <rounds>'");
 
-        assert_eq!(8, result);
+        let error = Day02.parse("Game 1: 99999999999 red").unwrap_err();
+        assert_eq!((error.line, error.message.as_str()), (Some(1), "invalid red cube count '99999999999'"));
+    }
+
+    #[test]
+    fn malformed_records() {
+        let error = |line| parse_record(line).unwrap_err().to_string();
+
+        assert_eq!(error("Game 1: 99999999999 red"), "column 9: invalid red cube count '99999999999'");
+        assert_eq!(error("Game x1: 3 blue"), "column 6: invalid game id 'x1'");
+        assert_eq!(error("Game 1: 3 blue,; 2 red"), "column 16: expected '<count> <colour>', not ''");
+        assert_eq!(error("Game 1: 3 blue; 2red"), "column 17: expected '<count> <colour>', not '2red'");
+        assert_eq!(error("Round 1: 3 blue"), "expected 'Game <id>: <rounds>'");
+        assert!(parse_line("Game 1:").is_err());
+    }
+
+    #[cfg(feature = "regex-parser")]
+    #[test]
+    fn parsers_agree() {
+        let input = generate_games(&mut Rng::new(5), 200);
+
+        for line in input.lines() {
+            let (id, game) = parse_line(line).unwrap();
+            assert_eq!(regex_parser::parse_line(line).unwrap(), (id, game), "{}", line);
+        }
     }
 }
\ No newline at end of file
@@ -1,34 +1,95 @@
-use crate::{Solution, SolutionPair};
-use std::fs::read_to_string;
-use std::error::Error;
+use crate::Solution;
+use crate::etc::day::{Day, Reference};
+use crate::etc::error::{AocError, AocResult};
+use crate::etc::rng::Rng;
 use regex::Regex;
+use std::collections::{BTreeMap, BTreeSet};
 
-pub fn solve() -> SolutionPair {
-    let contents = read_to_string("input/day03_input.txt").expect("failed to parse input file");
+pub struct Day03;
 
-    let result = calculate_part_number(&contents).expect("Failed to parse numbers.");
+impl Day for Day03 {
+    type Input<'a> = Schematic;
 
-    (Solution::from(result.0), Solution::from(result.1))
+    fn name(&self) -> &'static str {
+        "Gear Ratios"
+    }
+
+    fn parse<'a>(&self, input: &'a str) -> AocResult<Self::Input<'a>> {
+        parse_schematic(input)
+    }
+
+    fn part1(&self, input: &Self::Input<'_>) -> AocResult<Solution> {
+        input.part_number_sum().map(Solution::from)
+    }
+
+    fn part2(&self, input: &Self::Input<'_>) -> AocResult<Solution> {
+        input.gear_ratio_sum().map(Solution::from)
+    }
+
+    fn generate(&self, rng: &mut Rng, size: usize) -> Option<String> {
+        Some(generate_schematic(rng, size))
+    }
+
+    fn reference(&self) -> Option<Reference> {
+        Some(|input, _| check_every_neighbour(input).map(|(part1, part2)| (Solution::from(part1), Solution::from(part2))))
+    }
+}
+
+/// The engine schematic's numbers and symbols, with adjacency already resolved.
+pub struct Schematic {
+    part_numbers: Vec<PartNumber>,
+    symbols: Vec<Symbol>,
+}
+
+impl Schematic {
+    /// Sums the numbers adjacent to a symbol, failing if the sum doesn't fit
+    /// in a `usize`.
+    pub fn part_number_sum(&self) -> AocResult<usize> {
+        self.part_numbers
+            .iter()
+            .filter(|pn| pn.adjacent_to_symbol)
+            .try_fold(0usize, |sum, pn| sum.checked_add(pn.value))
+            .ok_or_else(|| AocError::new("the sum of the part numbers is too large"))
+    }
+
+    /// Sums the ratios of every `*` next to exactly two numbers, failing if
+    /// a ratio or the sum doesn't fit in a `usize`.
+    pub fn gear_ratio_sum(&self) -> AocResult<usize> {
+        self.symbols.iter().try_fold(0usize, |sum, s| {
+            let Some(ratio) = s.ratio.as_ref() else {
+                return Ok(sum);
+            };
+            let ratio = ratio.calculate_ratio().ok_or_else(|| {
+                AocError::new("gear ratio too large").at_line(s.line).at_column(s.position + 1)
+            })?;
+
+            sum.checked_add(ratio).ok_or_else(|| AocError::new("the sum of the gear ratios is too large"))
+        })
+    }
 }
 
-fn calculate_part_number(data: &str) -> Result<(usize,  usize), Box<dyn Error>> {
-    let regex = Regex::new(r"(?m)(\d+|[^\w\r\n.])").unwrap();
+pub fn parse_schematic(data: &str) -> AocResult<Schematic> {
+    let regex = Regex::new(r"(?m)(\d+|[^\w\r\n.])")?;
     
     let mut part_numbers = Vec::new();
     let mut symbols = Vec::new();
 
-    let mut line_number = 1;
-    let mut lines = data.lines();
-
-    while let Some(line) = lines.next() {
+    for (idx, line) in data.lines().enumerate() {
+        let line_number = idx + 1;
         let result = regex.captures_iter(line);
 
         for mat in result {
             let cap = mat.get(0).unwrap();
-    
-            match cap.as_str().parse::<usize>() {
-                Ok(val) => part_numbers.push(PartNumber::new(line_number, cap.start()..cap.end(), val)),
-                Err(_) => {
+            let text = cap.as_str();
+
+            match text.bytes().all(|b| b.is_ascii_digit()) {
+                true => {
+                    let val = text.parse().map_err(|_| {
+                        AocError::new(format!("number too large: '{}'", text)).at_line(line_number).at_column(cap.start() + 1)
+                    })?;
+                    part_numbers.push(PartNumber::new(line_number, cap.start()..cap.end(), val));
+                },
+                false => {
                     if cap.as_str().eq("*") {
                         symbols.push(Symbol { line: line_number, position: cap.start(), ratio: Some(GearRatio { part_one: None, part_two: None }) });
                     } else {
@@ -37,8 +98,6 @@ fn calculate_part_number(data: &str) -> Result<(usize,  usize), Box<dyn Error>>
                 },
             }
         }
-
-        line_number = line_number + 1;
     }
 
     for pn in part_numbers.iter_mut() {
@@ -47,21 +106,7 @@ fn calculate_part_number(data: &str) -> Result<(usize,  usize), Box<dyn Error>>
         }
     }
 
-    let result: usize = part_numbers
-        .iter()
-        .filter(|pn| pn.adjacent_to_symbol)
-        .map(|pn| pn.value)
-        .sum();
-
-    let gear_ratio: usize = symbols
-        .iter()
-        .filter(|s| s.ratio.is_some())
-        .map(|s| s.ratio.as_ref().unwrap().calculate_ratio())
-        .sum();
-
-    println!("{gear_ratio}");
-
-    Ok((result, gear_ratio))
+    Ok(Schematic { part_numbers, symbols })
 }
 
 #[derive(Debug)]
@@ -90,7 +135,7 @@ impl PartNumber {
         if self.position.contains(&test_range.start) || self.position.contains(&test_range.end) || self.position.contains(&symbol.position) {
             self.adjacent_to_symbol = true;
 
-            symbol.add_part_number(&self);
+            symbol.add_part_number(self);
         }
     }
 }
@@ -128,51 +173,130 @@ struct GearRatio {
 }
 
 impl GearRatio {
-    fn calculate_ratio(&self) -> usize {
-        if self.part_one.is_none() || self.part_two.is_none() {
-            0
-        }
-        else {
-            self.part_one.unwrap() * self.part_two.unwrap()
+    /// The product of both numbers, 0 if there aren't two, or None if it
+    /// doesn't fit in a `usize`.
+    fn calculate_ratio(&self) -> Option<usize> {
+        match (self.part_one, self.part_two) {
+            (Some(one), Some(two)) => one.checked_mul(two),
+            _ => Some(0),
         }
     }
 }
 
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+/// Reference for both parts: checks all eight neighbours of every digit of
+/// every number. Anything but a digit or a '.' is a symbol.
+fn check_every_neighbour(document: &str) -> AocResult<(usize, usize)> {
+    let grid: Vec<&[u8]> = document.lines().map(str::as_bytes).collect();
+    let cell = |row: isize, col: isize| -> u8 {
+        let row = grid.get(usize::try_from(row).unwrap_or(usize::MAX));
+        row.and_then(|line| line.get(usize::try_from(col).unwrap_or(usize::MAX)))
+            .copied()
+            .unwrap_or(b'.')
+    };
 
-    #[test]
-    fn part_number_is_adjacent() {
-        let mut part1 = PartNumber::new(0, 33..35, 16);
-        let mut part2 = PartNumber::new(1, 36..40, 1616);
-        let mut part3 = PartNumber::new(2, 32..34, 16);
-        let mut part4 = PartNumber::new(3, 33..35, 16);
-        let mut symbol = Symbol { line: 2, position: 35, ratio: None };
+    let mut part_number_sum = 0usize;
+    let mut gears: BTreeMap<(isize, isize), Vec<usize>> = BTreeMap::new();
 
-        part1.is_adjacent(&mut symbol);
-        part2.is_adjacent(&mut symbol);
-        part3.is_adjacent(&mut symbol);
-        part4.is_adjacent(&mut symbol);
-        assert_eq!(false, part1.adjacent_to_symbol);
-        assert_eq!(true, part2.adjacent_to_symbol);
-        assert_eq!(false, part3.adjacent_to_symbol);
-        assert_eq!(true, part4.adjacent_to_symbol);
+    for (row, line) in grid.iter().enumerate() {
+        let mut col = 0;
+
+        while col < line.len() {
+            if !line[col].is_ascii_digit() {
+                col += 1;
+                continue;
+            }
+
+            let start = col;
+            while col < line.len() && line[col].is_ascii_digit() {
+                col += 1;
+            }
+
+            let digits = std::str::from_utf8(&line[start..col]).unwrap();
+            let value: usize = digits.parse()
+                .map_err(|_| AocError::new(format!("number too large: '{}'", digits)).at_line(row + 1).at_column(start + 1))?;
+
+            let mut next_to_symbol = false;
+            let mut stars = BTreeSet::new();
+
+            for digit in start..col {
+                for (dr, dc) in (-1..=1).flat_map(|dr| (-1..=1).map(move |dc| (dr, dc))) {
+                    let (r, c) = (row as isize + dr, digit as isize + dc);
+                    let neighbour = cell(r, c);
+
+                    if !neighbour.is_ascii_digit() && neighbour != b'.' {
+                        next_to_symbol = true;
+                    }
+                    if neighbour == b'*' {
+                        stars.insert((r, c));
+                    }
+                }
+            }
+
+            if next_to_symbol {
+                part_number_sum = part_number_sum.checked_add(value)
+                    .ok_or_else(|| AocError::new("sum too large"))?;
+            }
+            for star in stars {
+                gears.entry(star).or_default().push(value);
+            }
+        }
     }
-    
-    #[test]
-    fn part_number_is_adjacent_2() {
-        let mut part1 = PartNumber::new(6, 11..12, 9);
-        let mut symbol = Symbol { line: 7, position: 11, ratio: None };
 
-        part1.is_adjacent(&mut symbol);
-        assert_eq!(true, part1.adjacent_to_symbol);
+    let mut gear_ratio_sum = 0usize;
+
+    for (&(row, col), numbers) in gears.iter().filter(|(_, numbers)| numbers.len() == 2) {
+        gear_ratio_sum = numbers[0].checked_mul(numbers[1])
+            .and_then(|ratio| gear_ratio_sum.checked_add(ratio))
+            .ok_or_else(|| AocError::new("gear ratio too large").at_line(row as usize + 1).at_column(col as usize + 1))?;
     }
 
-    #[test]
-    fn day_three_example() {
-        let example = String::from("467..114..
+    Ok((part_number_sum, gear_ratio_sum))
+}
+
+/// A `size` by `size` schematic of numbers and symbols scattered over dots.
+fn generate_schematic(rng: &mut Rng, size: usize) -> String {
+    const SYMBOLS: [char; 10] = ['*', '#', '+', '$', '/', '=', '%', '@', '&', '-'];
+
+    let mut document = String::new();
+
+    for _ in 0..size {
+        let mut row = String::new();
+
+        while row.len() < size {
+            let left = (size - row.len()) as u64;
+
+            if rng.chance(1, 6) {
+                row.push(char::from(b'1' + rng.below(9) as u8));
+                for _ in 1..rng.between(1, left.min(3)) {
+                    row.push(char::from(b'0' + rng.below(10) as u8));
+                }
+                // Keeps the number from running into the next one.
+                if row.len() < size {
+                    row.push('.');
+                }
+            } else if rng.chance(1, 10) {
+                row.push(*rng.choose(&SYMBOLS));
+            } else {
+                row.push('.');
+            }
+        }
+
+        document.push_str(&row);
+        document.push('\n');
+    }
+
+    document
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::etc::params::Params;
+
+    crate::examples! {
+        Day03;
+        day_three_example: "467..114..
 ...*......
 ..35..633.
 ......#...
@@ -181,18 +305,8 @@ mod tests {
 ..592.....
 ......755.
 ...$.*....
-.664.598..");
-
-
-        let result = calculate_part_number(example.as_str()).expect("Part Number failed to parse");
-
-        assert_eq!(4361, result.0);
-        assert_eq!(467835, result.1);
-    }
-
-    #[test]
-    fn example_2() {
-        let example = String::from("12.......*..
+.664.598.." => part1 = 4361, part2 = 467835;
+        example_2: "12.......*..
 +.........34
 .......-12..
 ..78........
@@ -203,18 +317,8 @@ mod tests {
 ............
 2.2......12.
 .*.........*
-1.1.......56");
-
-
-        let result = calculate_part_number(example.as_str()).expect("Part Number failed to parse");
-
-        assert_eq!(413, result.0);
-        assert_eq!(6756, result.1);
-    }
-
-    #[test]
-    fn example_3() {
-        let example = String::from("12.......*..
+1.1.......56" => part1 = 413, part2 = 6756;
+        example_3: "12.......*..
 +.........34
 .......-12..
 ..78........
@@ -225,12 +329,49 @@ mod tests {
 ............
 2.2......12.
 .*.........*
-1.1..503+.56");
+1.1..503+.56" => part1 = 925, part2 = 6756;
+    }
 
+    #[test]
+    fn numbers_too_large() {
+        let error = parse_schematic("..1.\n.*99999999999999999999999").err().unwrap();
+        assert_eq!(error.to_string(), "line 2 column 3: number too large: '99999999999999999999999'");
+
+        let input = "99999999999*99999999999";
+        let schematic = parse_schematic(input).unwrap();
+        assert_eq!(schematic.part_number_sum().unwrap(), 199999999998);
+        assert_eq!(schematic.gear_ratio_sum().unwrap_err().to_string(), "line 1 column 12: gear ratio too large");
+        assert_eq!(Day03.reference().unwrap()(input, &Params::new()).unwrap_err().to_string(),
+            "line 1 column 12: gear ratio too large");
+
+        let schematic = parse_schematic("18446744073709551615*1\n.........1...........").unwrap();
+        assert!(schematic.part_number_sum().is_err());
+    }
 
-        let result = calculate_part_number(example.as_str()).expect("Part Number failed to parse");
+    #[test]
+    fn part_number_is_adjacent() {
+        let mut part1 = PartNumber::new(0, 33..35, 16);
+        let mut part2 = PartNumber::new(1, 36..40, 1616);
+        let mut part3 = PartNumber::new(2, 32..34, 16);
+        let mut part4 = PartNumber::new(3, 33..35, 16);
+        let mut symbol = Symbol { line: 2, position: 35, ratio: None };
 
-        assert_eq!(925, result.0);
-        assert_eq!(6756, result.1);
+        part1.is_adjacent(&mut symbol);
+        part2.is_adjacent(&mut symbol);
+        part3.is_adjacent(&mut symbol);
+        part4.is_adjacent(&mut symbol);
+        assert!(!part1.adjacent_to_symbol);
+        assert!(part2.adjacent_to_symbol);
+        assert!(!part3.adjacent_to_symbol);
+        assert!(part4.adjacent_to_symbol);
+    }
+    
+    #[test]
+    fn part_number_is_adjacent_2() {
+        let mut part1 = PartNumber::new(6, 11..12, 9);
+        let mut symbol = Symbol { line: 7, position: 11, ratio: None };
+
+        part1.is_adjacent(&mut symbol);
+        assert!(part1.adjacent_to_symbol);
     }
 }
\ No newline at end of file
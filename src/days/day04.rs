@@ -1,14 +1,37 @@
-use crate::{Solution, SolutionPair};
-use std::fs::read_to_string;
-use std::error::Error;
+use crate::Solution;
+use crate::etc::day::{Day, Reference};
+use crate::etc::error::{column_of, AocError, AocResult};
+use crate::etc::rng::Rng;
 
-pub fn solve() -> SolutionPair {
-    let contents = read_to_string("input/day04_input.txt").expect("failed to parse input file");
+pub struct Day04;
 
-    let solution1 = calculate_points(&contents).expect("Failed to parse points.");
-    let solution2 = calculate_games(&contents).expect("Failed to parse games");
+impl Day for Day04 {
+    /// The number of winning numbers on each card.
+    type Input<'a> = Vec<u32>;
 
-    (Solution::from(solution1), Solution::from(solution2))
+    fn name(&self) -> &'static str {
+        "Scratchcards"
+    }
+
+    fn parse<'a>(&self, input: &'a str) -> AocResult<Self::Input<'a>> {
+        parse_cards(input)
+    }
+
+    fn part1(&self, input: &Self::Input<'_>) -> AocResult<Solution> {
+        Ok(Solution::from(calculate_points(input)))
+    }
+
+    fn part2(&self, input: &Self::Input<'_>) -> AocResult<Solution> {
+        Ok(Solution::from(calculate_games(input)))
+    }
+
+    fn generate(&self, rng: &mut Rng, size: usize) -> Option<String> {
+        Some(generate_cards(rng, size))
+    }
+
+    fn reference(&self) -> Option<Reference> {
+        Some(|input, _| simulate_every_copy(input).map(|(part1, part2)| (Solution::from(part1), Solution::from(part2))))
+    }
 }
 
 struct ScratchGame {
@@ -16,22 +39,34 @@ struct ScratchGame {
     number_of_wins: u32,
 }
 
-fn calculate_games(scratch_cards: &str) -> Result<usize, Box<dyn Error>> {
+/// Returns the number of winning numbers on each card.
+pub fn parse_cards(scratch_cards: &str) -> AocResult<Vec<u32>> {
 
     let lines = scratch_cards.lines();
 
-    let mut games = Vec::new();
+    let mut wins = Vec::new();
+
+    for (idx, line) in lines.enumerate() {
+        let numbers = |digits: &str| parse_digits(digits)
+            .map_err(|e| e.offset_column(column_of(line, digits) - 1));
 
-    for line in lines {
-        let scratch_components = split_card(line)?;
-        let winning_numbers = parse_digits(scratch_components.1)?;
-        let your_numbers = parse_digits(scratch_components.2)?;
+        let scratch_components = split_card(line).map_err(|e| e.at_line(idx + 1))?;
+        let winning_numbers = numbers(scratch_components.1).map_err(|e| e.at_line(idx + 1))?;
+        let your_numbers = numbers(scratch_components.2).map_err(|e| e.at_line(idx + 1))?;
 
-        let games_won = get_number_of_wins(&winning_numbers, &your_numbers);
-        let game = ScratchGame { copies: 1, number_of_wins: games_won };
-        games.push(game);
+        wins.push(get_number_of_wins(&winning_numbers, &your_numbers));
     }
 
+    Ok(wins)
+}
+
+/// The total number of scratchcards won, originals included.
+pub fn calculate_games(wins: &[u32]) -> usize {
+
+    let mut games: Vec<ScratchGame> = wins.iter()
+        .map(|&number_of_wins| ScratchGame { copies: 1, number_of_wins })
+        .collect();
+
     for counter in 0..games.len() {
 
         let split_games = &mut games.split_at_mut(counter + 1);
@@ -45,7 +80,7 @@ fn calculate_games(scratch_cards: &str) -> Result<usize, Box<dyn Error>> {
 
         for idx in 0..wins {
             if let Some(next_game) = split_games.1.get_mut(idx) {
-                next_game.copies = next_game.copies + game.copies;
+                next_game.copies += game.copies;
             }
         }
     }
@@ -54,37 +89,31 @@ fn calculate_games(scratch_cards: &str) -> Result<usize, Box<dyn Error>> {
         .map(|g| g.copies)
         .sum();
 
-    Ok(usize::try_from(result).unwrap())
+    usize::try_from(result).unwrap()
 }
 
-fn calculate_points(scratch_cards: &str) -> Result<usize, Box<dyn Error>> {
-
-    let lines = scratch_cards.lines();
+/// The points each card is worth summed: 1 for the first win, doubling after.
+pub fn calculate_points(wins: &[u32]) -> usize {
 
     let mut total_points = 0;
 
-    for line in lines {
-        let scratch_components = split_card(line)?;
-        let winning_numbers = parse_digits(scratch_components.1)?;
-        let your_numbers = parse_digits(scratch_components.2)?;
-
-        let games_won = get_number_of_wins(&winning_numbers, &your_numbers);
+    for &games_won in wins {
         if games_won > 0 {
-            total_points = total_points + (2usize.pow(games_won-1));
+            total_points += 2usize.pow(games_won-1);
         }
     }
 
-    Ok(total_points)
+    total_points
 }
 
-fn get_number_of_wins(winning_numbers: &Vec<usize>, your_numbers: &Vec<usize>) -> u32 {
+fn get_number_of_wins(winning_numbers: &[usize], your_numbers: &[usize]) -> u32 {
 
     let mut games_won = 0;
 
     for winning_number in winning_numbers.iter() {
         for your_number in your_numbers.iter() {
             if your_number == winning_number {
-                games_won = games_won + 1;
+                games_won += 1;
                 break;
             }
         }
@@ -93,94 +122,162 @@ fn get_number_of_wins(winning_numbers: &Vec<usize>, your_numbers: &Vec<usize>) -
     games_won
 }
 
-fn split_card(scratch_card: &str) -> Result<(&str, &str, &str), &'static str> {
+/// Splits a card into its name, winning numbers and your numbers, however
+/// they're spaced.
+fn split_card(scratch_card: &str) -> AocResult<(&str, &str, &str)> {
 
-    let colon_idx = match scratch_card.find(":") {
-        Some(val) => val,
-        None => return Err("Invalid scratch card, no colon."),
-    };
+    let (card, numbers) = scratch_card.split_once(':')
+        .ok_or_else(|| AocError::new("no ':' separator"))?;
 
-    let pipe_idx = match scratch_card.find("|") {
-        Some(val) => val,
-        None => return Err("Invalid scratch card, no pipe."),
-    };
+    let (winning, yours) = numbers.split_once('|')
+        .ok_or_else(|| AocError::new("no '|' separator"))?;
+
+    if winning.trim().is_empty() {
+        return Err(AocError::new("expected winning numbers between ':' and '|'").at_column(card.len() + 1));
+    }
 
-    Ok((&scratch_card[0..colon_idx], &scratch_card[colon_idx+2..pipe_idx-1], &scratch_card[pipe_idx+2..]))
+    Ok((card.trim(), winning.trim(), yours.trim()))
 }
 
-fn parse_digits(digits: &str) -> Result<Vec<usize>, Box<dyn Error>> {
+fn parse_digits(digits: &str) -> AocResult<Vec<usize>> {
 
-    let digit_items = digits.split(" ");
+    let digit_items = digits.split(' ');
 
     let mut result = Vec::new();
 
     for digit in digit_items {
-        if digit.eq("") {
+        if digit.is_empty() {
             continue;
         }
 
-        result.push(digit.parse::<usize>()?);
+        let number = digit.parse::<usize>()
+            .map_err(|_| AocError::new(format!("not a number: '{}'", digit)).at_column(column_of(digits, digit)))?;
+        result.push(number);
     }
 
     Ok(result)
 }
 
+/// Reference for both parts: looks up every winning number among yours, and
+/// works through a pile holding every single copy of every card won.
+fn simulate_every_copy(document: &str) -> AocResult<(usize, usize)> {
+    let mut matches = Vec::new();
+
+    for (idx, line) in document.lines().enumerate() {
+        let error = |message: String| AocError::new(message).at_line(idx + 1);
+
+        let (winning, yours) = line.split_once(':')
+            .and_then(|(_, numbers)| numbers.split_once('|'))
+            .ok_or_else(|| error(String::from("expected 'Card <id>: <numbers> | <numbers>'")))?;
+        let numbers = |numbers: &str| numbers.split_whitespace()
+            .map(|number| number.parse::<u32>().map_err(|_| error(format!("not a number: '{}'", number))))
+            .collect::<AocResult<Vec<_>>>();
+
+        let (winning, yours) = (numbers(winning)?, numbers(yours)?);
+        if winning.is_empty() {
+            return Err(error(String::from("expected winning numbers")));
+        }
+
+        matches.push(winning.iter().filter(|number| yours.contains(number)).count());
+    }
+
+    let points = matches.iter()
+        .filter(|&&count| count > 0)
+        .map(|&count| 1 << (count - 1))
+        .sum();
+
+    let mut pile: Vec<usize> = (0..matches.len()).collect();
+    let mut cards = 0;
+
+    while let Some(card) = pile.pop() {
+        cards += 1;
+        pile.extend((card + 1..=card + matches[card]).filter(|&won| won < matches.len()));
+    }
+
+    Ok((points, cards))
+}
+
+/// Cards with 10 winning numbers and 25 of yours, all below 100. Most cards
+/// win nothing: with a mean of less than one match per card, the copies won
+/// in part 2 don't grow exponentially with the number of cards.
+fn generate_cards(rng: &mut Rng, cards: usize) -> String {
+    let numbers = |numbers: &[u64]| {
+        numbers.iter().map(|n| format!("{:>2}", n)).collect::<Vec<_>>().join(" ")
+    };
+
+    let mut document = String::new();
+
+    for card in 1..=cards {
+        let mut pool: Vec<u64> = (1..100).collect();
+        rng.shuffle(&mut pool);
+
+        let matches = match rng.chance(7, 10) {
+            true => 0,
+            // Cards never win copies of cards past the end of the table.
+            false => (rng.between(1, 3) as usize).min(cards - card),
+        };
+
+        let winning = &pool[..10];
+        let mut yours = winning[..matches].to_vec();
+        yours.extend(&pool[10..35 - matches]);
+        rng.shuffle(&mut yours);
+
+        document.push_str(&format!("Card {:>3}: {} | {}\n", card, numbers(winning), numbers(&yours)));
+    }
+
+    document
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    crate::examples! {
+        Day04;
+        example: "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
+Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
+Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
+Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
+Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
+Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11" => part1 = 13, part2 = 30;
+    }
+
     #[test]
     fn example_split_card() {
         let example = "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53";
-        let result = split_card(&example).expect("Invalid Parse");
+        let result = split_card(example).expect("Invalid Parse");
 
         assert_eq!(result.0, "Card 1");
         assert_eq!(result.1, "41 48 83 86 17");
         assert_eq!(result.2, "83 86  6 31 17  9 48 53");
+
+        let result = split_card("Card   12:41 48|83  86 ").expect("Invalid Parse");
+
+        assert_eq!(result, ("Card   12", "41 48", "83  86"));
     }
 
     #[test]
     fn example_parse_digits() {
         let example = "41 48 83 86 17";
-        let result = parse_digits(&example).expect("Invalid Parse");
+        let result = parse_digits(example).expect("Invalid Parse");
 
         assert_eq!(result, vec![41, 48, 83, 86, 17]);
 
         let example = "83 86  6 31 17  9 48 53";
-        let result = parse_digits(&example).expect("Invalid Parse");
+        let result = parse_digits(example).expect("Invalid Parse");
 
         assert_eq!(result, vec![83, 86, 6, 31, 17, 9, 48, 53]);
     }
 
     #[test]
-    fn example() {
-        let example = 
-"Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
-Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
-Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
-Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
-Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
-Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11";
-
-
-        let result = calculate_points(example).expect("Part Number failed to parse");
-
-        assert_eq!(13, result);
-    }
-
-    #[test]
-    fn example_two() {
-        let example = 
-"Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
-Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
-Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
-Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
-Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
-Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11";
-
+    fn malformed_cards() {
+        let error = parse_cards("Card 1: 41 48 | 83 86\nCard 2: 13 32   61 30").unwrap_err();
+        assert_eq!(error.to_string(), "line 2: no '|' separator");
 
-        let result = calculate_games(example).expect("Part Number failed to parse");
+        let error = parse_cards("Card 1: 41 4x | 83 86").unwrap_err();
+        assert_eq!(error.to_string(), "line 1 column 12: not a number: '4x'");
 
-        assert_eq!(30, result);
+        let error = parse_cards("Card 1:| 83 86").unwrap_err();
+        assert_eq!(error.to_string(), "line 1 column 7: expected winning numbers between ':' and '|'");
     }
 }
\ No newline at end of file
@@ -1,28 +1,39 @@
-use crate::{Solution, SolutionPair};
-use std::fs::read_to_string;
+use crate::Solution;
+use crate::etc::day::Day;
+use crate::etc::error::AocResult;
 
-pub fn solve() -> SolutionPair {
+pub struct Day07;
 
-    let contents = read_to_string("input/day01_input.txt").expect("failed to parse input file");
+impl Day for Day07 {
+    type Input<'a> = &'a str;
 
-    let mut lines = contents.lines();
+    fn name(&self) -> &'static str {
+        "Camel Cards"
+    }
 
-    while let Some(_line) = lines.next() {
+    fn is_implemented(&self) -> bool {
+        false
+    }
 
+    fn parse<'a>(&self, input: &'a str) -> AocResult<Self::Input<'a>> {
+        Ok(input)
     }
 
-    let solution1 = 0;
-    let solution2 = 0;
+    fn part1(&self, _input: &Self::Input<'_>) -> AocResult<Solution> {
+        Ok(Solution::Pending)
+    }
 
-    (Solution::from(solution1), Solution::from(solution2))
+    fn part2(&self, _input: &Self::Input<'_>) -> AocResult<Solution> {
+        Ok(Solution::Pending)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    #[test]
-    fn day07_example() {
-        let _ = solve();
+    crate::examples! {
+        Day07;
+        example: "" => part1 = Solution::Pending, part2 = Solution::Pending;
     }
 }
\ No newline at end of file
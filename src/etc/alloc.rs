@@ -0,0 +1,183 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::fmt::{self, Display, Formatter};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// A global allocator that counts what each thread allocates, for `track`.
+/// It does nothing unless installed with `#[global_allocator]`, which the
+/// runner does when built with the `count-alloc` feature.
+pub struct CountingAlloc;
+
+static INSTALLED: AtomicBool = AtomicBool::new(false);
+
+#[derive(Clone, Copy)]
+struct Counters {
+    allocations: u64,
+    bytes: u64,
+    live: u64,
+    peak: u64,
+}
+
+thread_local! {
+    static COUNTERS: Cell<Counters> = const {
+        Cell::new(Counters { allocations: 0, bytes: 0, live: 0, peak: 0 })
+    };
+}
+
+fn update(f: impl FnOnce(&mut Counters)) {
+    // Fails while the thread is shutting down, when nothing is tracked anyway.
+    let _ = COUNTERS.try_with(|cell| {
+        let mut counters = cell.get();
+        f(&mut counters);
+        cell.set(counters);
+    });
+}
+
+fn record_alloc(size: usize) {
+    INSTALLED.store(true, Ordering::Relaxed);
+
+    update(|counters| {
+        counters.allocations += 1;
+        counters.bytes += size as u64;
+        counters.live += size as u64;
+        counters.peak = counters.peak.max(counters.live);
+    });
+}
+
+fn record_free(size: usize) {
+    // Memory can be freed by a different thread than the one that allocated
+    // it, so a thread's live bytes can't be trusted not to go negative.
+    update(|counters| counters.live = counters.live.saturating_sub(size as u64));
+}
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = unsafe { System.alloc(layout) };
+        if !ptr.is_null() {
+            record_alloc(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = unsafe { System.alloc_zeroed(layout) };
+        if !ptr.is_null() {
+            record_alloc(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) };
+        record_free(layout.size());
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = unsafe { System.realloc(ptr, layout, new_size) };
+        if !new_ptr.is_null() {
+            record_free(layout.size());
+            record_alloc(new_size);
+        }
+        new_ptr
+    }
+}
+
+/// What the current thread allocated while running a closure.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct AllocStats {
+    pub allocations: u64,
+    pub bytes: u64,
+    /// The most memory that was live at once, beyond what was live before.
+    pub peak: u64,
+}
+
+impl Display for AllocStats {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{} allocs, {} allocated, {} peak", self.allocations, Bytes(self.bytes), Bytes(self.peak))
+    }
+}
+
+/// A byte count in the largest binary unit that keeps it above 1.
+struct Bytes(u64);
+
+impl Display for Bytes {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+
+        if self.0 < 1024 {
+            return write!(f, "{} B", self.0);
+        }
+
+        let mut value = self.0 as f64;
+        let mut unit = 0;
+
+        value /= 1024.0;
+        while value >= 1024.0 && unit + 1 < UNITS.len() {
+            value /= 1024.0;
+            unit += 1;
+        }
+
+        write!(f, "{:.1} {}", value, UNITS[unit])
+    }
+}
+
+/// Whether `CountingAlloc` is the global allocator.
+pub fn is_counting() -> bool {
+    INSTALLED.load(Ordering::Relaxed)
+}
+
+/// Runs `f`, returning its result together with what it allocated on this
+/// thread, or `None` if allocations aren't being counted.
+pub fn track<T>(f: impl FnOnce() -> T) -> (T, Option<AllocStats>) {
+    if !is_counting() {
+        return (f(), None);
+    }
+
+    let mut start = COUNTERS.get();
+    start.peak = start.live;
+    COUNTERS.set(start);
+
+    let result = f();
+    let end = COUNTERS.get();
+
+    let stats = AllocStats {
+        allocations: end.allocations - start.allocations,
+        bytes: end.bytes - start.bytes,
+        peak: end.peak - start.live,
+    };
+
+    (result, Some(stats))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[global_allocator]
+    static ALLOC: CountingAlloc = CountingAlloc;
+
+    #[test]
+    fn counting() {
+        let _warm_up = String::from("installs the allocator");
+
+        let (_, stats) = track(|| {
+            let first = vec![0u8; 1000];
+            drop(first);
+            let second = vec![0u8; 600];
+            second.len()
+        });
+        let stats = stats.expect("allocations are counted");
+
+        assert_eq!(stats.allocations, 2);
+        assert_eq!(stats.bytes, 1600);
+        assert_eq!(stats.peak, 1000);
+    }
+
+    #[test]
+    fn display() {
+        let stats = AllocStats { allocations: 3, bytes: 1536, peak: 512 };
+
+        assert_eq!(stats.to_string(), "3 allocs, 1.5 KiB allocated, 512 B peak");
+        assert_eq!(Bytes(5 * 1024 * 1024).to_string(), "5.0 MiB");
+    }
+}
@@ -0,0 +1,174 @@
+use crate::{Solution, SolutionPair};
+use crate::etc::error::AocResult;
+use crate::etc::measure::{measure, Measurement};
+use crate::etc::params::Params;
+use crate::etc::rng::Rng;
+
+/// A reference solver: both answers straight from the input and params.
+pub type Reference = fn(&str, &Params) -> AocResult<SolutionPair>;
+
+/// A single day's puzzle: how to read its input and how to answer both parts.
+pub trait Day {
+    /// The parsed puzzle input handed to both parts.
+    type Input<'a>;
+
+    /// The puzzle's title.
+    fn name(&self) -> &'static str;
+
+    /// Whether the day has a real solver rather than a stub.
+    fn is_implemented(&self) -> bool {
+        true
+    }
+
+    /// Turns the raw puzzle input into the model both parts work on.
+    fn parse<'a>(&self, input: &'a str) -> AocResult<Self::Input<'a>>;
+
+    /// Like `parse`, with settings given at runtime. Days that take any
+    /// override this; the rest reject every parameter.
+    fn parse_with<'a>(&self, input: &'a str, params: &Params) -> AocResult<Self::Input<'a>> {
+        params.expect_only(&[])?;
+        self.parse(input)
+    }
+
+    fn part1(&self, input: &Self::Input<'_>) -> AocResult<Solution>;
+
+    fn part2(&self, input: &Self::Input<'_>) -> AocResult<Solution>;
+
+    /// Generates a structurally valid input of roughly `size` records (lines,
+    /// games, cards...), or `None` if the day has no generator.
+    fn generate(&self, _rng: &mut Rng, _size: usize) -> Option<String> {
+        None
+    }
+
+    /// Answers both parts the slow and obvious way, straight from the input
+    /// and params, to check `part1` and `part2` against. `None` if the day
+    /// has no reference solver.
+    fn reference(&self) -> Option<Reference> {
+        None
+    }
+}
+
+/// Object-safe view of a `Day`, so days with different input types can
+/// share one registry.
+pub trait Solver: Sync {
+    fn name(&self) -> &'static str;
+
+    fn is_implemented(&self) -> bool;
+
+    /// Parses `input` and runs the selected part (both if `part` is `None`),
+    /// measuring each phase separately.
+    fn run(&self, input: &str, part: Option<u8>) -> AocResult<Run> {
+        self.run_with(input, part, &Params::new())
+    }
+
+    /// Like `run`, parsing the input with `Day::parse_with`.
+    fn run_with(&self, input: &str, part: Option<u8>, params: &Params) -> AocResult<Run>;
+
+    /// Generates an input from `seed`, see `Day::generate`.
+    fn generate(&self, seed: u64, size: usize) -> Option<String>;
+
+    /// Whether the day has a reference solver, see `Day::reference`.
+    fn has_reference(&self) -> bool;
+
+    /// Answers both parts with the reference solver, see `Day::reference`.
+    fn reference(&self, input: &str, params: &Params) -> Option<AocResult<SolutionPair>>;
+}
+
+/// The answer to one part and what it took to compute it.
+#[derive(Clone, Debug)]
+pub struct PartRun {
+    pub answer: Solution,
+    pub measurement: Measurement,
+}
+
+/// The outcome of running a day: the parse phase and each part that was run.
+#[derive(Clone, Debug)]
+pub struct Run {
+    pub parse: Measurement,
+    pub part1: Option<PartRun>,
+    pub part2: Option<PartRun>,
+}
+
+impl<D: Day + Sync> Solver for D {
+    fn name(&self) -> &'static str {
+        Day::name(self)
+    }
+
+    fn is_implemented(&self) -> bool {
+        Day::is_implemented(self)
+    }
+
+    fn run_with(&self, input: &str, part: Option<u8>, params: &Params) -> AocResult<Run> {
+        let (parsed, parse) = measure(|| self.parse_with(input, params));
+        let parsed = parsed?;
+
+        let part1 = match part {
+            None | Some(1) => {
+                let (answer, measurement) = measure(|| self.part1(&parsed));
+                Some(PartRun { answer: answer?, measurement })
+            },
+            _ => None,
+        };
+        let part2 = match part {
+            None | Some(2) => {
+                let (answer, measurement) = measure(|| self.part2(&parsed));
+                Some(PartRun { answer: answer?, measurement })
+            },
+            _ => None,
+        };
+
+        Ok(Run { parse, part1, part2 })
+    }
+
+    fn generate(&self, seed: u64, size: usize) -> Option<String> {
+        Day::generate(self, &mut Rng::new(seed), size)
+    }
+
+    fn has_reference(&self) -> bool {
+        Day::reference(self).is_some()
+    }
+
+    fn reference(&self, input: &str, params: &Params) -> Option<AocResult<SolutionPair>> {
+        Day::reference(self).map(|reference| reference(input, params))
+    }
+}
+
+/// A day number together with its solver.
+pub struct Entry {
+    pub day: u8,
+    pub solver: &'static dyn Solver,
+}
+
+/// One Advent of Code season: its year and registered days.
+pub struct Season {
+    pub year: u16,
+    /// The module the days live in, under `src/`.
+    pub module: &'static str,
+    pub days: &'static [Entry],
+}
+
+impl Season {
+    /// Looks up the solver registered for `day`.
+    pub fn get(&self, day: u8) -> Option<&'static Entry> {
+        self.days.iter().find(|entry| entry.day == day)
+    }
+}
+
+/// Declares the day modules and builds `DAYS`, the registry the runner
+/// looks solvers up in. Each entry is `day => module::Type`.
+#[macro_export]
+macro_rules! register_days {
+    ($($day:literal => $module:ident::$solver:ident),* $(,)?) => {
+        $(pub mod $module;)*
+
+        /// Every registered day, in order.
+        pub static DAYS: &[$crate::etc::day::Entry] = &[
+            $($crate::etc::day::Entry { day: $day, solver: &$module::$solver },)*
+        ];
+
+        /// Looks up the solver registered for `day`.
+        pub fn get(day: u8) -> Option<&'static $crate::etc::day::Entry> {
+            DAYS.iter().find(|entry| entry.day == day)
+        }
+    };
+}
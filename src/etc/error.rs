@@ -0,0 +1,103 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter, Result};
+
+pub type AocResult<T> = std::result::Result<T, AocError>;
+
+/// A failure while solving a day, pointing at where in the input it happened.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AocError {
+    pub day: Option<u8>,
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+    pub message: String,
+}
+
+impl AocError {
+    pub fn new(message: impl Into<String>) -> Self {
+        AocError { day: None, line: None, column: None, message: message.into() }
+    }
+
+    pub fn in_day(mut self, day: u8) -> Self {
+        self.day = Some(day);
+        self
+    }
+
+    /// Sets the 1-based line number, unless one is already set.
+    pub fn at_line(mut self, line: usize) -> Self {
+        self.line.get_or_insert(line);
+        self
+    }
+
+    /// Sets the 1-based column, unless one is already set.
+    pub fn at_column(mut self, column: usize) -> Self {
+        self.column.get_or_insert(column);
+        self
+    }
+
+    /// Shifts the column right by `offset`, for errors found in a slice that
+    /// starts `offset` bytes into the line.
+    pub fn offset_column(mut self, offset: usize) -> Self {
+        self.column = self.column.map(|column| column + offset);
+        self
+    }
+}
+
+/// The 1-based column `part` starts at, where `part` is a slice of `line`.
+pub fn column_of(line: &str, part: &str) -> usize {
+    part.as_ptr() as usize - line.as_ptr() as usize + 1
+}
+
+impl Display for AocError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        let mut location = Vec::new();
+
+        if let Some(day) = self.day {
+            location.push(format!("day{:02}", day));
+        }
+        if let Some(line) = self.line {
+            location.push(format!("line {}", line));
+        }
+        if let Some(column) = self.column {
+            location.push(format!("column {}", column));
+        }
+
+        if location.is_empty() {
+            write!(f, "{}", self.message)
+        } else {
+            write!(f, "{}: {}", location.join(" "), self.message)
+        }
+    }
+}
+
+impl Error for AocError {}
+
+impl From<regex::Error> for AocError {
+    fn from(error: regex::Error) -> Self {
+        AocError::new(error.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display() {
+        let error = AocError::new("no '|' separator");
+        assert_eq!(error.to_string(), "no '|' separator");
+
+        let error = error.at_line(17).in_day(4);
+        assert_eq!(error.to_string(), "day04 line 17: no '|' separator");
+
+        let error = error.at_column(3).at_line(20).offset_column(5);
+        assert_eq!(error.to_string(), "day04 line 17 column 8: no '|' separator");
+    }
+
+    #[test]
+    fn column() {
+        let line = "Card 1: 41 48";
+        let (_, numbers) = line.split_once(": ").unwrap();
+
+        assert_eq!(column_of(line, numbers), 9);
+    }
+}
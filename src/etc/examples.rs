@@ -0,0 +1,59 @@
+/// Declares a day's puzzle examples together with their expected answers,
+/// generating a test module per example with one `#[test]` per part:
+///
+/// ```ignore
+/// crate::examples! {
+///     Day04;
+///     example: "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53" => part1 = 8, part2 = 1;
+///     only_part_two: "..." => part2 = 30;
+/// }
+/// ```
+///
+/// The expected answers can be anything that converts into a `Solution`.
+/// By default inputs are dedented and normalized first, like the runner does
+/// with real inputs, so they can be indented along with the code around them.
+/// An input written as `raw "..."` is passed on as is, like with `--raw`:
+///
+/// ```ignore
+/// crlf: raw "1abc2\r\ntreb7uchet\r\n" => part1 = 89;
+/// ```
+#[macro_export]
+macro_rules! examples {
+    ($day:expr; $($examples:tt)*) => {
+        $crate::examples!(@each $day; $($examples)*);
+    };
+    (@each $day:expr;) => {};
+    (@each $day:expr; $name:ident: raw $input:expr => $($part:ident = $expected:expr),+; $($rest:tt)*) => {
+        $crate::examples!(@test $day; $name; ::std::string::String::from($input); $($part = $expected),+);
+        $crate::examples!(@each $day; $($rest)*);
+    };
+    (@each $day:expr; $name:ident: $input:expr => $($part:ident = $expected:expr),+; $($rest:tt)*) => {
+        $crate::examples!(
+            @test $day; $name;
+            $crate::etc::input::normalize(&$crate::etc::input::dedent($input));
+            $($part = $expected),+
+        );
+        $crate::examples!(@each $day; $($rest)*);
+    };
+    (@test $day:expr; $name:ident; $input:expr; $($part:ident = $expected:expr),+) => {
+        mod $name {
+            use super::*;
+
+            $(
+                #[test]
+                fn $part() {
+                    use $crate::etc::day::Day;
+
+                    let day = $day;
+                    let input: ::std::string::String = $input;
+                    let parsed = day.parse(&input)
+                        .unwrap_or_else(|e| panic!("example failed to parse: {}", e));
+                    let answer = day.$part(&parsed)
+                        .unwrap_or_else(|e| panic!("{} failed: {}", stringify!($part), e));
+
+                    assert_eq!(answer, $crate::etc::solution::Solution::from($expected));
+                }
+            )+
+        }
+    };
+}
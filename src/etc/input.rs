@@ -0,0 +1,74 @@
+/// Cleans up a puzzle input so parsers only ever see one layout: the byte
+/// order mark is dropped, line endings become `\n` and trailing blank lines
+/// are removed. Whitespace within lines is kept, since some puzzles pad them.
+/// The result ends in a single newline unless it is empty.
+pub fn normalize(input: &str) -> String {
+    let input = input.strip_prefix('\u{feff}').unwrap_or(input);
+    let mut result = String::with_capacity(input.len());
+
+    for line in input.lines() {
+        result.push_str(line.strip_suffix('\r').unwrap_or(line));
+        result.push('\n');
+    }
+
+    let trimmed = result.trim_end_matches('\n').len();
+    result.truncate(trimmed);
+    if !result.is_empty() {
+        result.push('\n');
+    }
+
+    result
+}
+
+/// Removes the indentation a multi-line string literal picks up from the
+/// code around it. The first line starts right after the opening quote, so
+/// it is kept as is (or dropped if empty) and the common indentation of the
+/// other lines is removed.
+pub fn dedent(text: &str) -> String {
+    let (first, rest) = match text.split_once('\n') {
+        Some((first, rest)) => (first, rest),
+        None => return text.to_string(),
+    };
+
+    let indent = rest.lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.len() - line.trim_start().len())
+        .min()
+        .unwrap_or(0);
+
+    let mut lines = Vec::new();
+
+    if !first.trim().is_empty() {
+        lines.push(first);
+    }
+    for line in rest.lines() {
+        lines.push(line.get(indent..).unwrap_or_else(|| line.trim_start()));
+    }
+
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalizing() {
+        assert_eq!(normalize("\u{feff}Card 1: 1 | 2\r\nCard 2: 3 | 4  \r\n\r\n\n"), "Card 1: 1 | 2\nCard 2: 3 | 4  \n");
+        assert_eq!(normalize("1abc2\n\n  pqr3 \t"), "1abc2\n\n  pqr3 \t\n");
+        assert_eq!(normalize("#.. \r\n .#\r"), "#.. \n .#\n");
+        assert_eq!(normalize("\n\n"), "");
+    }
+
+    #[test]
+    fn dedenting() {
+        assert_eq!(dedent("1abc2
+            pqr3stu8vwx
+              a1b2c3d4e5f"), "1abc2\npqr3stu8vwx\n  a1b2c3d4e5f");
+        assert_eq!(dedent("
+            467..114..
+
+            ...*......"), "467..114..\n\n...*......");
+        assert_eq!(dedent("Game 1: 3 blue"), "Game 1: 3 blue");
+    }
+}
@@ -0,0 +1,26 @@
+use crate::etc::alloc::{self, AllocStats};
+use std::time::{Duration, Instant};
+
+/// What was measured while running one phase (parse, part 1 or part 2).
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Measurement {
+    pub elapsed: Duration,
+    /// Only counted when `CountingAlloc` is the global allocator.
+    pub allocations: Option<AllocStats>,
+}
+
+impl Measurement {
+    pub fn as_millis(&self) -> f64 {
+        self.elapsed.as_nanos() as f64 / 1_000_000.0
+    }
+}
+
+/// Runs `f`, returning its result together with how long it took and what
+/// it allocated.
+pub fn measure<T>(f: impl FnOnce() -> T) -> (T, Measurement) {
+    let time = Instant::now();
+    let (result, allocations) = alloc::track(f);
+    let elapsed = time.elapsed();
+
+    (result, Measurement { elapsed, allocations })
+}
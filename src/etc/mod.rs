@@ -1 +1,9 @@
+pub mod alloc;
+pub mod day;
+pub mod error;
+pub mod examples;
+pub mod input;
+pub mod measure;
+pub mod params;
+pub mod rng;
 pub mod solution;
\ No newline at end of file
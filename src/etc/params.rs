@@ -0,0 +1,70 @@
+use crate::etc::error::{AocError, AocResult};
+use std::collections::BTreeMap;
+
+/// Settings a day can be run with besides its input, as `key=value` pairs.
+/// Days that take none reject any.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Params {
+    values: BTreeMap<String, String>,
+}
+
+impl Params {
+    pub fn new() -> Self {
+        Params::default()
+    }
+
+    /// Sets `key`, replacing any earlier value.
+    pub fn set(&mut self, key: impl Into<String>, value: impl Into<String>) {
+        self.values.insert(key.into(), value.into());
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.values.get(key).map(String::as_str)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Fails on the first key that isn't one of `known`.
+    pub fn expect_only(&self, known: &[&str]) -> AocResult<()> {
+        match self.values.keys().find(|key| !known.contains(&key.as_str())) {
+            Some(key) if known.is_empty() => Err(AocError::new(format!("unknown parameter '{}', the day takes none", key))),
+            Some(key) => Err(AocError::new(format!("unknown parameter '{}' (expected {})", key, known.join(", ")))),
+            None => Ok(()),
+        }
+    }
+
+    /// Reads `key` as `true` or `false`, `default` if it isn't set.
+    pub fn flag(&self, key: &str, default: bool) -> AocResult<bool> {
+        match self.get(key) {
+            None => Ok(default),
+            Some("false") => Ok(false),
+            Some("true") => Ok(true),
+            Some(value) => Err(AocError::new(format!("parameter '{}' must be true or false, not '{}'", key, value))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn params() {
+        let mut params = Params::new();
+        assert_eq!(params.expect_only(&[]), Ok(()));
+
+        params.set("ignore-case", "yes");
+        params.set("vocabulary", "uno = 1");
+
+        assert_eq!(params.get("vocabulary"), Some("uno = 1"));
+        assert!(params.expect_only(&["vocabulary", "ignore-case"]).is_ok());
+        assert_eq!(params.expect_only(&["vocabulary"]).unwrap_err().message,
+            "unknown parameter 'ignore-case' (expected vocabulary)");
+        assert_eq!(params.expect_only(&[]).unwrap_err().message, "unknown parameter 'ignore-case', the day takes none");
+        assert!(params.flag("ignore-case", false).is_err());
+        assert_eq!(params.flag("missing", false), Ok(false));
+        assert_eq!(params.flag("missing", true), Ok(true));
+    }
+}
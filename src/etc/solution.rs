@@ -1,11 +1,59 @@
+use std::convert::Infallible;
 use std::fmt::{Display, Formatter, Result};
+use std::str::FromStr;
 use Solution::*;
 
-#[derive(Clone)]
+#[derive(Clone, Debug)]
 pub enum Solution {
     I32(i32),
     U32(u32),
     USize(usize),
+    I64(i64),
+    U64(u64),
+    I128(i128),
+    U128(u128),
+    Str(String),
+    /// An answer spread over several lines, e.g. letters drawn on a grid.
+    Text(String),
+    /// The day (or part) has not been solved yet.
+    Pending,
+}
+
+impl Solution {
+    pub fn is_pending(&self) -> bool {
+        matches!(self, Pending)
+    }
+
+    /// Returns the variant name, e.g. `"U64"`.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            I32(_) => "I32",
+            U32(_) => "U32",
+            USize(_) => "USize",
+            I64(_) => "I64",
+            U64(_) => "U64",
+            I128(_) => "I128",
+            U128(_) => "U128",
+            Str(_) => "Str",
+            Text(_) => "Text",
+            Pending => "Pending",
+        }
+    }
+
+    /// Splits an integer answer into its sign and magnitude so answers of
+    /// different widths can be compared.
+    fn as_integer(&self) -> Option<(bool, u128)> {
+        match *self {
+            I32(x) => Some((x < 0, x.unsigned_abs() as u128)),
+            U32(x) => Some((false, x as u128)),
+            USize(x) => Some((false, x as u128)),
+            I64(x) => Some((x < 0, x.unsigned_abs() as u128)),
+            U64(x) => Some((false, x as u128)),
+            I128(x) => Some((x < 0, x.unsigned_abs())),
+            U128(x) => Some((false, x)),
+            _ => None,
+        }
+    }
 }
 
 impl Display for Solution {
@@ -14,6 +62,65 @@ impl Display for Solution {
             I32(x) => x.fmt(f),
             U32(x) => x.fmt(f),
             USize(x) => x.fmt(f),
+            I64(x) => x.fmt(f),
+            U64(x) => x.fmt(f),
+            I128(x) => x.fmt(f),
+            U128(x) => x.fmt(f),
+            Str(x) => x.fmt(f),
+            Text(x) => x.fmt(f),
+            Pending => "pending".fmt(f),
+        }
+    }
+}
+
+impl PartialEq for Solution {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Str(a) | Text(a), Str(b) | Text(b)) => a == b,
+            (Pending, Pending) => true,
+            (Pending, _) | (_, Pending) => false,
+            // A text answer made of digits reads back as an integer.
+            (Str(_) | Text(_), _) | (_, Str(_) | Text(_)) => self.to_string() == other.to_string(),
+            _ => match (self.as_integer(), other.as_integer()) {
+                (Some((neg_a, a)), Some((neg_b, b))) => a == b && (neg_a == neg_b || a == 0),
+                _ => false,
+            },
+        }
+    }
+}
+
+/// Parses an answer as written by `Display`: integers become the narrowest
+/// of `U64`, `I64`, `U128` and `I128`, `pending` becomes `Pending`, and
+/// anything else is kept as text. Only integers written the way `Display`
+/// writes them count, so text like `0123` or `+5` stays text, whitespace
+/// included.
+impl FromStr for Solution {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        if s.is_empty() || s == "pending" {
+            return Ok(Pending);
+        }
+
+        let canonical = |x: &dyn Display| x.to_string() == s;
+
+        if let Some(x) = s.parse::<u64>().ok().filter(|x| canonical(x)) {
+            return Ok(U64(x));
+        }
+        if let Some(x) = s.parse::<i64>().ok().filter(|x| canonical(x)) {
+            return Ok(I64(x));
+        }
+        if let Some(x) = s.parse::<u128>().ok().filter(|x| canonical(x)) {
+            return Ok(U128(x));
+        }
+        if let Some(x) = s.parse::<i128>().ok().filter(|x| canonical(x)) {
+            return Ok(I128(x));
+        }
+
+        if s.contains('\n') {
+            Ok(Text(s.to_string()))
+        } else {
+            Ok(Str(s.to_string()))
         }
     }
 }
@@ -31,3 +138,61 @@ macro_rules! impl_from {
 impl_from!(i32, I32);
 impl_from!(u32, U32);
 impl_from!(usize, USize);
+impl_from!(i64, I64);
+impl_from!(u64, U64);
+impl_from!(i128, I128);
+impl_from!(u128, U128);
+impl_from!(String, Str);
+
+impl From<&str> for Solution {
+    fn from(sol: &str) -> Self {
+        Self::Str(sol.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn equal_across_widths() {
+        assert_eq!(USize(5), U64(5));
+        assert_eq!(I32(-7), I128(-7));
+        assert_eq!(U128(u64::MAX as u128), U64(u64::MAX));
+        assert_eq!(I32(0), U32(0));
+        assert_ne!(I32(-5), U32(5));
+        assert_eq!(U32(5), Str(String::from("5")));
+        assert_ne!(U32(5), Str(String::from("5.0")));
+        assert_ne!(U32(0), Pending);
+    }
+
+    #[test]
+    fn parse_round_trip() {
+        let answers = [
+            U64(142),
+            I64(-3),
+            U128(u128::MAX),
+            I128(i128::MIN),
+            Str(String::from("abc")),
+            Str(String::from("0123")),
+            Str(String::from("+5")),
+            Str(String::from("-0")),
+            Text(String::from("#..#\n####")),
+            Text(String::from("  #.\n#.  ")),
+            Pending,
+        ];
+
+        for answer in answers {
+            let parsed: Solution = answer.to_string().parse().unwrap();
+            assert_eq!(parsed, answer);
+            assert_eq!(parsed.kind(), answer.kind());
+        }
+
+        // Only the text survives for an answer that looks like a number.
+        let answer = Str(String::from("19636"));
+        let parsed: Solution = answer.to_string().parse().unwrap();
+        assert_eq!(parsed, answer);
+        assert_eq!(answer, parsed);
+        assert_ne!(Str(String::from("019636")), U64(19636));
+    }
+}
@@ -18,75 +18,226 @@
 // OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 // SOFTWARE.
 
-mod days;
-mod etc;
+mod runner;
 
-use etc::solution::Solution;
-use days::{day01, day02, day03, day04, day05, day06, day07, day08, day09,
-        day10, day11, day12, day13, day14, day15, day16, day17, day18, day19,
-        day20, day21, day22, day23, day24, day25 };
+use advent_2023::years;
+use runner::cli::{self, Command, GenOptions, Options};
+use runner::answers::{self, Answers, Verdict};
+use runner::bench;
+use runner::check;
+use runner::input;
+use runner::pool::{self, Failure};
+use runner::format::{Format, Output};
+use runner::report::DayReport;
+use runner::scaffold;
+use runner::watch;
 use std::env;
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::sync::Arc;
 use std::time::Instant;
 
-pub type SolutionPair = (Solution, Solution);
+#[cfg(feature = "count-alloc")]
+#[global_allocator]
+static ALLOC: advent_2023::etc::alloc::CountingAlloc = advent_2023::etc::alloc::CountingAlloc;
 
-fn main() {
-    let args: Vec<String> = env::args().collect();
-    if args.len() < 2 {
-        panic!("Please provide the day(s) to run as a command-line argument.");
+fn main() -> ExitCode {
+    let success = match cli::parse(env::args().skip(1), years::YEARS) {
+        Ok(Command::Run(options)) => return run(&options),
+        Ok(Command::Bench(options)) => bench::run(&options),
+        Ok(Command::Watch(options)) => watch::run(&options),
+        Ok(Command::NewDay { year, day, name }) => new_day(year, day, name.as_deref()),
+        Ok(Command::Gen(options)) => generate(&options),
+        Ok(Command::Check(options)) => check::run(&options),
+        Ok(Command::List(year)) => {
+            list_days(year);
+            true
+        },
+        Ok(Command::Help) => {
+            println!("{}", cli::USAGE);
+            true
+        },
+        Err(message) => {
+            eprintln!("error: {}\n\nRun with --help for usage.", message);
+            return ExitCode::from(2);
+        },
+    };
+
+    if success {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
     }
+}
 
-    let days: Vec<u8> = args[1..].iter()
-        .map(|x| x.parse().unwrap_or_else(|v| panic!("Not a valid day: {}", v)))
-        .collect();
+/// Generates the module for `day` and tells the user where its input goes.
+fn new_day(year: u16, day: u8, name: Option<&str>) -> bool {
+    let season = years::get(year).expect("year was checked by the parser");
+
+    match scaffold::new_day(&scaffold::source_dir(season), season, day, name) {
+        Ok(changes) => {
+            for change in changes {
+                println!("{}", change);
+            }
+            println!("Put the puzzle input in {}", input::default_path(year, day).display());
+            true
+        },
+        Err(message) => {
+            eprintln!("error: {}", message);
+            false
+        },
+    }
+}
 
-    let mut runtime = 0.0;
+/// Prints a generated input for the day.
+fn generate(options: &GenOptions) -> bool {
+    let season = years::get(options.year).expect("year was checked by the parser");
 
-    for day in days {
-        let func = get_day_solver(day);
+    match season.get(options.day).and_then(|entry| entry.solver.generate(options.seed, options.size)) {
+        Some(input) => {
+            print!("{}", input);
+            true
+        },
+        None => {
+            eprintln!("error: day {} has no input generator", options.day);
+            false
+        },
+    }
+}
 
-        let time = Instant::now();
-        let (p1, p2) = func();
-        let elapsed_ms = time.elapsed().as_nanos() as f64 / 1_000_000.0;
+/// Runs every selected day. Fails with 1 if a day could not be run or gave
+/// an answer that doesn't match the answers file, and with 3 if a day
+/// panicked or timed out.
+fn run(options: &Options) -> ExitCode {
+    let mut summed_ms = 0.0;
+    let mut failed = Vec::new();
+    let mut crashed = false;
 
-        println!("\n=== Day {:02} ===", day);
-        println!("  . Part 1: {}", p1);
-        println!("  . Part 2: {}", p2);
-        println!("  . Elapsed: {:.4} ms", elapsed_ms);
+    // Verified answers only apply to the real inputs with the default
+    // settings, so a custom --input or --param is only checked against an
+    // answers file named explicitly.
+    let answers_path = options.answers.clone()
+        .or_else(|| (options.input.is_none() && options.params.is_empty()).then(|| PathBuf::from(answers::DEFAULT_PATH)));
+    let checking = answers_path.as_ref().is_some_and(|path| path.exists());
 
-        runtime += elapsed_ms;
+    if let Some(path) = options.answers.as_ref().filter(|_| !checking && !options.record) {
+        eprintln!("error: answers file not found: {}", path.display());
+        return ExitCode::FAILURE;
     }
 
-    println!("Total runtime: {:.4} ms", runtime);
-}
+    let answers = match answers_path.as_deref().filter(|_| checking) {
+        Some(path) => match Answers::load(path) {
+            Ok(answers) => answers,
+            Err(message) => {
+                eprintln!("error: {}", message);
+                return ExitCode::FAILURE;
+            },
+        },
+        None => Answers::default(),
+    };
+
+    let mut recorded = Answers::default();
+    let mut output = Output::new(options.format, options.quiet);
+    let wall_clock = Instant::now();
+
+    output.begin();
+    pool::capture_panics();
+
+    let shared = Arc::new(options.clone());
+    let work = move |&day: &u8| solve_day(day, &shared);
+
+    pool::run_ordered(options.jobs, &options.days, options.timeout, work, |&day, result| {
+        let mut report = match result {
+            Ok(Ok(report)) => report,
+            Ok(Err(message)) => {
+                output.error(day, &message);
+                failed.push(format!("day{:02} (error)", day));
+                return;
+            },
+            Err(failure) => {
+                output.failure(day, &failure);
+                let what = match failure {
+                    Failure::Panic(_) => "panicked",
+                    Failure::Timeout(_) => "timed out",
+                };
+                failed.push(format!("day{:02} ({})", day, what));
+                crashed = true;
+                return;
+            },
+        };
+        let mut wrong = false;
+
+        for part in report.parts.iter_mut() {
+            if options.record {
+                if !part.answer.is_pending() {
+                    recorded.set(options.year, day, part.part, part.answer.clone());
+                }
+            } else if checking {
+                let verdict = answers.check(options.year, day, part.part, &part.answer);
+
+                if let Verdict::Wrong(expected) = &verdict {
+                    if options.quiet || options.format != Format::Text {
+                        eprintln!("day{:02} part{}: expected {}, got {}", day, part.part, expected, part.answer);
+                    }
+                    wrong = true;
+                }
+                part.verdict = Some(verdict);
+            }
+        }
+
+        if wrong {
+            failed.push(format!("day{:02} (wrong answer)", day));
+        }
 
-fn get_day_solver(day: u8) -> fn() -> SolutionPair {
-    match day {
-        1 => day01::solve,
-        2 => day02::solve,
-        3 => day03::solve,
-        4 => day04::solve,
-        5 => day05::solve,
-        6 => day06::solve,
-        7 => day07::solve,
-        8 => day08::solve,
-        9 => day09::solve,
-        10 => day10::solve,
-        11 => day11::solve,
-        12 => day12::solve,
-        13 => day13::solve,
-        14 => day14::solve,
-        15 => day15::solve,
-        16 => day16::solve,
-        17 => day17::solve,
-        18 => day18::solve,
-        19 => day19::solve,
-        20 => day20::solve,
-        21 => day21::solve,
-        22 => day22::solve,
-        23 => day23::solve,
-        24 => day24::solve,
-        25 => day25::solve,
-        _ => unimplemented!(),
+        output.day(&report);
+        summed_ms += report.elapsed_ms();
+    });
+
+    let wall_clock_ms = wall_clock.elapsed().as_nanos() as f64 / 1_000_000.0;
+
+    output.finish(summed_ms, wall_clock_ms, options.jobs);
+
+    // The parser refuses --record without an answers file to write to.
+    if let Some(path) = answers_path.filter(|_| options.record) {
+        if let Err(message) = recorded.record(&path) {
+            eprintln!("error: {}", message);
+            return ExitCode::FAILURE;
+        }
+        if options.format == Format::Text && !options.quiet {
+            println!("Recorded answers in {}", path.display());
+        }
+    }
+
+    if failed.is_empty() {
+        return ExitCode::SUCCESS;
+    }
+
+    eprintln!("error: {} of {} days failed: {}", failed.len(), options.days.len(), failed.join(", "));
+
+    if crashed {
+        ExitCode::from(3)
+    } else {
+        ExitCode::FAILURE
     }
 }
+
+/// Runs one day `repeat` times on a worker thread.
+fn solve_day(day: u8, options: &Options) -> Result<DayReport, String> {
+    let (entry, contents) = input::resolve(options.year, day, options.input.as_deref(), options.raw)?;
+
+    let runs = (0..options.repeat)
+        .map(|_| entry.solver.run_with(&contents, options.part, &options.params))
+        .collect::<Result<_, _>>()
+        .map_err(|e| e.in_day(day).to_string())?;
+
+    Ok(DayReport::from_runs(day, runs))
+}
+
+fn list_days(year: u16) {
+    let season = years::get(year).expect("year was checked by the parser");
+
+    for entry in season.days {
+        let status = if entry.solver.is_implemented() { "" } else { " (not implemented)" };
+        println!("{:>2}  {}{}", entry.day, entry.solver.name(), status);
+    }
+}
\ No newline at end of file
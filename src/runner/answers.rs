@@ -0,0 +1,270 @@
+use advent_2023::etc::solution::Solution;
+use advent_2023::years;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+pub const DEFAULT_PATH: &str = "answers.txt";
+
+/// How a part's answer compares to the verified one.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Verdict {
+    Correct,
+    Wrong(Solution),
+    Unknown,
+}
+
+impl Verdict {
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Verdict::Correct => "✔",
+            Verdict::Wrong(_) => "✘",
+            Verdict::Unknown => "unknown",
+        }
+    }
+}
+
+/// Verified answers keyed by year, day and part, stored in a small TOML-like
+/// file:
+///
+/// ```text
+/// [2023/day01]
+/// part1 = 142
+/// part2 = 281
+/// ```
+///
+/// A section without a year, like `[day01]`, belongs to the default season.
+/// Multi-line answers are written on one line with `\n` escapes, and spaces
+/// at either end of an answer as `\s` so they aren't trimmed away.
+#[derive(Debug, Default, PartialEq)]
+pub struct Answers {
+    entries: BTreeMap<(u16, u8, u8), Solution>,
+}
+
+impl Answers {
+    /// Reads the answers file at `path`; a missing file has no answers.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        if !path.exists() {
+            return Ok(Answers::default());
+        }
+
+        let contents = fs::read_to_string(path)
+            .map_err(|e| format!("failed to read answers from {}: {}", path.display(), e))?;
+
+        Answers::parse(&contents)
+            .map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    pub fn parse(contents: &str) -> Result<Self, String> {
+        let mut answers = Answers::default();
+        let mut section = None;
+
+        for (idx, line) in contents.lines().enumerate() {
+            let line_number = idx + 1;
+            let line = line.trim();
+
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                section = parse_section(name);
+                if section.is_none() {
+                    return Err(format!("line {}: not a day section: '{}'", line_number, line));
+                }
+                continue;
+            }
+
+            let Some((key, value)) = line.split_once('=') else {
+                return Err(format!("line {}: expected 'partN = answer'", line_number));
+            };
+            let Some((year, day)) = section else {
+                return Err(format!("line {}: answer outside of a [dayNN] section", line_number));
+            };
+            let part = match key.trim() {
+                "part1" => 1,
+                "part2" => 2,
+                other => return Err(format!("line {}: unknown key '{}'", line_number, other)),
+            };
+
+            let answer = unescape(value.trim()).parse().unwrap_or(Solution::Pending);
+            answers.set(year, day, part, answer);
+        }
+
+        Ok(answers)
+    }
+
+    pub fn get(&self, year: u16, day: u8, part: u8) -> Option<&Solution> {
+        self.entries.get(&(year, day, part))
+    }
+
+    pub fn set(&mut self, year: u16, day: u8, part: u8, answer: Solution) {
+        self.entries.insert((year, day, part), answer);
+    }
+
+    pub fn check(&self, year: u16, day: u8, part: u8, answer: &Solution) -> Verdict {
+        match self.get(year, day, part) {
+            None => Verdict::Unknown,
+            Some(expected) if expected.is_pending() => Verdict::Unknown,
+            Some(expected) if expected == answer => Verdict::Correct,
+            Some(expected) => Verdict::Wrong(expected.clone()),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        fs::write(path, self.to_string())
+            .map_err(|e| format!("failed to write answers to {}: {}", path.display(), e))
+    }
+
+    /// Writes these answers into the file at `path`, keeping the ones it
+    /// already has for other days and parts.
+    pub fn record(&self, path: &Path) -> Result<(), String> {
+        let mut saved = Answers::load(path)?;
+        saved.entries.extend(self.entries.iter().map(|(key, answer)| (*key, answer.clone())));
+        saved.save(path)
+    }
+}
+
+impl std::fmt::Display for Answers {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "# Verified answers, checked by the runner and updated with --record.")?;
+
+        let mut section = None;
+
+        for (&(year, day, part), answer) in &self.entries {
+            if section != Some((year, day)) {
+                writeln!(f, "\n[{}/day{:02}]", year, day)?;
+                section = Some((year, day));
+            }
+            writeln!(f, "part{} = {}", part, escape(&answer.to_string()))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Parses a section name, `2023/day01` or just `day01`.
+fn parse_section(name: &str) -> Option<(u16, u8)> {
+    let (year, day) = match name.split_once('/') {
+        Some((year, day)) => (year.parse().ok()?, day),
+        None => (years::DEFAULT, name),
+    };
+
+    Some((year, day.strip_prefix("day")?.parse().ok()?))
+}
+
+fn escape(value: &str) -> String {
+    let escaped = value.replace('\\', "\\\\").replace('\n', "\\n");
+    let inner = escaped.trim_matches(' ');
+    let leading = escaped.len() - escaped.trim_start_matches(' ').len();
+    let trailing = escaped.len() - escaped.trim_end_matches(' ').len();
+
+    match inner.is_empty() {
+        true => "\\s".repeat(escaped.len()),
+        false => format!("{}{}{}", "\\s".repeat(leading), inner, "\\s".repeat(trailing)),
+    }
+}
+
+fn unescape(value: &str) -> String {
+    let mut result = String::new();
+    let mut chars = value.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+
+        match chars.next() {
+            Some('n') => result.push('\n'),
+            Some('s') => result.push(' '),
+            Some(other) => result.push(other),
+            None => result.push('\\'),
+        }
+    }
+
+    result
+}
+
+/// Formats a part's verdict for the text output, e.g. `✘ (expected 142)`.
+pub fn describe(verdict: &Verdict) -> String {
+    let mut text = String::from(verdict.symbol());
+
+    if let Verdict::Wrong(expected) = verdict {
+        let _ = write!(text, " (expected {})", escape(&expected.to_string()));
+    }
+
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_and_check() {
+        let answers = Answers::parse("# comment
+[day01]
+part1 = 142
+part2 = 281
+
+[day05]
+part1 = pending
+
+[2024/day01]
+part1 = 11
+").unwrap();
+
+        assert_eq!(answers.check(2023, 1, 1, &Solution::U32(142)), Verdict::Correct);
+        assert_eq!(answers.check(2023, 1, 2, &Solution::USize(280)), Verdict::Wrong(Solution::U64(281)));
+        assert_eq!(answers.check(2023, 5, 1, &Solution::U32(1)), Verdict::Unknown);
+        assert_eq!(answers.check(2023, 2, 1, &Solution::U32(1)), Verdict::Unknown);
+        assert_eq!(answers.check(2024, 1, 1, &Solution::U32(11)), Verdict::Correct);
+        assert_eq!(answers.check(2024, 1, 2, &Solution::U32(142)), Verdict::Unknown);
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut answers = Answers::default();
+        answers.set(2023, 3, 2, Solution::USize(467835));
+        answers.set(2024, 1, 1, Solution::Text(String::from("#..#\n####")));
+        answers.set(2024, 1, 2, Solution::Text(String::from("  #.\n #. ")));
+        answers.set(2024, 2, 1, Solution::Str(String::from("0123")));
+        answers.set(2024, 2, 2, Solution::Str(String::from("+5")));
+        answers.set(2024, 3, 1, Solution::Str(String::from(" ")));
+
+        let parsed = Answers::parse(&answers.to_string()).unwrap();
+        assert_eq!(parsed, answers);
+        // Text stays text, even where it looks like a number.
+        for (key, answer) in answers.entries.iter().filter(|(_, answer)| matches!(answer, Solution::Str(_) | Solution::Text(_))) {
+            assert_eq!(parsed.entries[key].kind(), answer.kind());
+        }
+        assert!(answers.to_string().contains("part2 = \\s\\s#.\\n #.\\s\n"));
+    }
+
+    #[test]
+    fn recording_keeps_other_days() {
+        let path = std::env::temp_dir().join(format!("advent_answers_{}", std::process::id()));
+        fs::write(&path, "[2023/day01]\npart1 = 142\npart2 = 281\n\n[2023/day02]\npart1 = 8\n").unwrap();
+
+        let mut run = Answers::default();
+        run.set(2023, 1, 1, Solution::U32(209));
+        run.record(&path).unwrap();
+        let saved = Answers::load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(saved.get(2023, 1, 1), Some(&Solution::U64(209)));
+        assert_eq!(saved.get(2023, 1, 2), Some(&Solution::U64(281)));
+        assert_eq!(saved.get(2023, 2, 1), Some(&Solution::U64(8)));
+    }
+
+    #[test]
+    fn errors() {
+        assert!(Answers::parse("part1 = 5").is_err());
+        assert!(Answers::parse("[dayx]").is_err());
+        assert!(Answers::parse("[23x/day01]").is_err());
+        assert!(Answers::parse("[day01]\npart3 = 5").is_err());
+        assert!(Answers::parse("[day01]\npart1").is_err());
+    }
+}
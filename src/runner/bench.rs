@@ -0,0 +1,338 @@
+use advent_2023::etc::day::{PartRun, Run};
+use advent_2023::years;
+use crate::runner::cli::BenchOptions;
+use crate::runner::input;
+use crate::runner::pool;
+use std::collections::BTreeMap;
+use std::fmt::{self, Display, Formatter};
+use std::fs;
+use std::path::Path;
+use std::time::Instant;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Phase {
+    Parse,
+    Part1,
+    Part2,
+}
+
+impl Phase {
+    pub const ALL: [Phase; 3] = [Phase::Parse, Phase::Part1, Phase::Part2];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Phase::Parse => "parse",
+            Phase::Part1 => "part1",
+            Phase::Part2 => "part2",
+        }
+    }
+
+    fn label(&self) -> &'static str {
+        match self {
+            Phase::Parse => "Parse",
+            Phase::Part1 => "Part 1",
+            Phase::Part2 => "Part 2",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Phase> {
+        Phase::ALL.into_iter().find(|phase| phase.as_str() == name)
+    }
+
+    /// How long this phase took in `run`, if it was run at all.
+    fn sample(&self, run: &Run) -> Option<f64> {
+        let part = |part: &Option<PartRun>| part.as_ref().map(|p| p.measurement.as_millis());
+
+        match self {
+            Phase::Parse => Some(run.parse.as_millis()),
+            Phase::Part1 => part(&run.part1),
+            Phase::Part2 => part(&run.part2),
+        }
+    }
+}
+
+/// Summary statistics over a phase's samples, in milliseconds.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Stats {
+    pub samples: usize,
+    pub min: f64,
+    pub median: f64,
+    pub mean: f64,
+    pub stddev: f64,
+}
+
+impl Stats {
+    pub fn from_samples(samples: &mut [f64]) -> Option<Self> {
+        if samples.is_empty() {
+            return None;
+        }
+
+        samples.sort_by(f64::total_cmp);
+
+        let count = samples.len();
+        let mid = count / 2;
+        let median = if count.is_multiple_of(2) {
+            (samples[mid - 1] + samples[mid]) / 2.0
+        } else {
+            samples[mid]
+        };
+        let mean = samples.iter().sum::<f64>() / count as f64;
+        let variance = samples.iter()
+            .map(|s| (s - mean).powi(2))
+            .sum::<f64>() / count as f64;
+
+        Some(Stats { samples: count, min: samples[0], median, mean, stddev: variance.sqrt() })
+    }
+}
+
+impl Display for Stats {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "min {:.4} ms | median {:.4} ms | mean {:.4} ms | stddev {:.4} ms",
+            self.min, self.median, self.mean, self.stddev)
+    }
+}
+
+/// Benchmark results keyed by year, day and phase, as saved in
+/// `bench_output.txt`. Rows without a year belong to the default season.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Results {
+    entries: BTreeMap<(u16, u8, Phase), Stats>,
+}
+
+impl Results {
+    /// Reads previous results from `path`; a missing file has no results.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        if !path.exists() {
+            return Ok(Results::default());
+        }
+
+        let contents = fs::read_to_string(path)
+            .map_err(|e| format!("failed to read benchmark results from {}: {}", path.display(), e))?;
+
+        Results::parse(&contents)
+            .map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    pub fn parse(contents: &str) -> Result<Self, String> {
+        let mut results = Results::default();
+
+        for (idx, line) in contents.lines().enumerate() {
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let fields: Vec<&str> = line.split('\t').collect();
+            let invalid = || format!("line {}: not a benchmark result: '{}'", idx + 1, line);
+
+            let (year, fields) = match fields.len() {
+                8 => (fields[0].parse::<u16>().map_err(|_| invalid())?, &fields[1..]),
+                _ => (years::DEFAULT, &fields[..]),
+            };
+            let [day, phase, samples, min, median, mean, stddev] = fields[..] else {
+                return Err(invalid());
+            };
+            let number = |field: &str| field.parse::<f64>().map_err(|_| invalid());
+
+            let day = day.parse::<u8>().map_err(|_| invalid())?;
+            let phase = Phase::from_name(phase).ok_or_else(invalid)?;
+            let stats = Stats {
+                samples: samples.parse().map_err(|_| invalid())?,
+                min: number(min)?,
+                median: number(median)?,
+                mean: number(mean)?,
+                stddev: number(stddev)?,
+            };
+
+            results.entries.insert((year, day, phase), stats);
+        }
+
+        Ok(results)
+    }
+
+    pub fn get(&self, year: u16, day: u8, phase: Phase) -> Option<&Stats> {
+        self.entries.get(&(year, day, phase))
+    }
+
+    pub fn insert(&mut self, year: u16, day: u8, phase: Phase, stats: Stats) {
+        self.entries.insert((year, day, phase), stats);
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        fs::write(path, self.to_string())
+            .map_err(|e| format!("failed to write benchmark results to {}: {}", path.display(), e))
+    }
+}
+
+impl Display for Results {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(f, "# year\tday\tphase\tsamples\tmin_ms\tmedian_ms\tmean_ms\tstddev_ms")?;
+
+        for (&(year, day, phase), s) in &self.entries {
+            writeln!(f, "{}\t{:02}\t{}\t{}\t{:.6}\t{:.6}\t{:.6}\t{:.6}",
+                year, day, phase.as_str(), s.samples, s.min, s.median, s.mean, s.stddev)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Relative change of the median against a previous result, in percent.
+fn change(previous: &Stats, current: &Stats) -> f64 {
+    if previous.median == 0.0 {
+        return 0.0;
+    }
+
+    (current.median - previous.median) / previous.median * 100.0
+}
+
+/// Benchmarks every selected day, saves the results and compares them with
+/// the previous ones. Returns false if a day couldn't be run or regressed.
+pub fn run(options: &BenchOptions) -> bool {
+    let previous = match Results::load(&options.output) {
+        Ok(previous) => previous,
+        Err(message) => {
+            eprintln!("error: {}", message);
+            return false;
+        },
+    };
+
+    let mut results = previous.clone();
+    let mut success = true;
+    let mut regressions = 0;
+
+    pool::capture_panics();
+
+    for &day in &options.days {
+        let (entry, contents) = match input::resolve(options.year, day, options.input.as_deref(), options.raw) {
+            Ok(resolved) => resolved,
+            Err(message) => {
+                eprintln!("error: {}", message);
+                success = false;
+                continue;
+            },
+        };
+
+        let mut samples: BTreeMap<Phase, Vec<f64>> = BTreeMap::new();
+        let mut count = 0;
+        let mut start = Instant::now();
+
+        for round in 0.. {
+            // A day that panics is reported and skipped, the others still run.
+            let run = match pool::catch(|| entry.solver.run_with(&contents, options.part, &options.params)) {
+                Ok(Ok(run)) => run,
+                Ok(Err(error)) => {
+                    eprintln!("error: {}", error.in_day(day));
+                    success = false;
+                    break;
+                },
+                Err(failure) => {
+                    println!("\n=== Day {:02} ===\n  . {}", day, failure);
+                    success = false;
+                    break;
+                },
+            };
+
+            if round < options.warmup {
+                start = Instant::now();
+                continue;
+            }
+
+            for phase in Phase::ALL {
+                if let Some(sample) = phase.sample(&run) {
+                    samples.entry(phase).or_default().push(sample);
+                }
+            }
+            count += 1;
+
+            let done = match options.runs {
+                Some(runs) => count >= runs,
+                None => start.elapsed() >= options.budget,
+            };
+            if done {
+                break;
+            }
+        }
+
+        if count == 0 {
+            continue;
+        }
+
+        println!("\n=== Day {:02} === ({} runs)", day, count);
+
+        for (phase, mut phase_samples) in samples {
+            let Some(stats) = Stats::from_samples(&mut phase_samples) else {
+                continue;
+            };
+
+            let comparison = match previous.get(options.year, day, phase) {
+                Some(prev) => {
+                    let delta = change(prev, &stats);
+                    let flag = if delta > options.threshold {
+                        regressions += 1;
+                        ", REGRESSION"
+                    } else {
+                        ""
+                    };
+                    format!(" [{:+.1}% vs {:.4} ms{}]", delta, prev.median, flag)
+                },
+                None => String::new(),
+            };
+
+            println!("  . {:<7} {}{}", format!("{}:", phase.label()), stats, comparison);
+            results.insert(options.year, day, phase, stats);
+        }
+    }
+
+    if let Err(message) = results.save(&options.output) {
+        eprintln!("error: {}", message);
+        return false;
+    }
+
+    println!("\nSaved results in {}", options.output.display());
+
+    if regressions > 0 {
+        println!("{} phase(s) regressed by more than {}%", regressions, options.threshold);
+    }
+
+    success && regressions == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stats() {
+        let stats = Stats::from_samples(&mut [4.0, 1.0, 3.0, 2.0]).unwrap();
+
+        assert_eq!(stats.samples, 4);
+        assert_eq!(stats.min, 1.0);
+        assert_eq!(stats.median, 2.5);
+        assert_eq!(stats.mean, 2.5);
+        assert!((stats.stddev - 1.25f64.sqrt()).abs() < 1e-9);
+        assert!(Stats::from_samples(&mut []).is_none());
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut results = Results::default();
+        results.insert(2023, 1, Phase::Part2, Stats { samples: 10, min: 0.5, median: 1.0, mean: 1.25, stddev: 0.125 });
+        results.insert(2024, 1, Phase::Parse, Stats { samples: 10, min: 0.25, median: 0.5, mean: 0.5, stddev: 0.0 });
+
+        assert_eq!(Results::parse(&results.to_string()).unwrap(), results);
+        assert!(Results::parse("01\tparse\t10").is_err());
+        assert!(Results::parse("01\tpart3\t10\t1\t1\t1\t1").is_err());
+
+        // Rows from before results had a year belong to the default season.
+        let legacy = Results::parse("01\tparse\t10\t0.25\t0.5\t0.5\t0").unwrap();
+        assert_eq!(legacy.get(2023, 1, Phase::Parse).map(|s| s.median), Some(0.5));
+    }
+
+    #[test]
+    fn regression() {
+        let previous = Stats { samples: 10, min: 1.0, median: 2.0, mean: 2.0, stddev: 0.0 };
+        let current = Stats { median: 2.5, ..previous };
+
+        assert_eq!(change(&previous, &current), 25.0);
+    }
+}
@@ -0,0 +1,183 @@
+use advent_2023::etc::day::Entry;
+use advent_2023::etc::solution::Solution;
+use advent_2023::years;
+use crate::runner::cli::CheckOptions;
+use crate::runner::input;
+use crate::runner::pool;
+
+/// The answers to the checked parts, or why there are none.
+type Outcome = Result<[Option<Solution>; 2], String>;
+
+/// Runs a day and its reference solver on generated inputs, or on the given
+/// one, and stops at the first input they disagree on. That input is cut
+/// down to the fewest lines that still show the disagreement and printed
+/// along with both answers.
+pub fn run(options: &CheckOptions) -> bool {
+    let Some(entry) = years::get(options.year).and_then(|season| season.get(options.day)) else {
+        eprintln!("error: no solver registered for day {} of {}", options.day, options.year);
+        return false;
+    };
+    if !entry.solver.has_reference() {
+        eprintln!("error: day {} has no reference solver", options.day);
+        return false;
+    }
+
+    pool::capture_panics();
+
+    let inputs: Vec<(String, String)> = match &options.input {
+        Some(path) => match input::load(options.year, options.day, Some(path), options.raw) {
+            Ok(contents) => vec![(path.display().to_string(), contents)],
+            Err(message) => {
+                eprintln!("error: {}", message);
+                return false;
+            },
+        },
+        None => {
+            let mut inputs = Vec::new();
+
+            for seed in (options.seed..).take(options.cases as usize) {
+                let Some(contents) = entry.solver.generate(seed, options.size) else {
+                    eprintln!("error: day {} has no input generator, check it with --input", options.day);
+                    return false;
+                };
+                inputs.push((format!("seed {}", seed), contents));
+            }
+
+            inputs
+        },
+    };
+
+    println!("Checking day {} ({}) against its reference on {} input(s)", options.day, entry.solver.name(), inputs.len());
+
+    let disagree = |input: &str| {
+        let (optimized, reference) = solve(entry, input, options);
+        !agree(&optimized, &reference)
+    };
+
+    for (name, contents) in &inputs {
+        if !disagree(contents) {
+            continue;
+        }
+
+        let minimized = minimize(contents, disagree);
+        let (optimized, reference) = solve(entry, &minimized, options);
+
+        println!("Disagreement on {}, minimized to {} line(s):\n", name, minimized.lines().count());
+        print!("{}", minimized);
+        println!();
+        println!("  . Optimized: {}", describe(&optimized));
+        println!("  . Reference: {}", describe(&reference));
+
+        return false;
+    }
+
+    println!("All agree");
+    true
+}
+
+/// Runs both solvers on `input`, keeping only the answers to the part that
+/// is checked.
+fn solve(entry: &Entry, input: &str, options: &CheckOptions) -> (Outcome, Outcome) {
+    let (part, params) = (options.part, &options.params);
+    let keep = |answer: Option<Solution>, number: u8| answer.filter(|_| part.is_none_or(|part| part == number));
+
+    let optimized = pool::catch(|| entry.solver.run_with(input, part, params))
+        .map_err(|failure| failure.to_string())
+        .and_then(|run| run.map_err(|e| e.to_string()))
+        .map(|run| [run.part1.map(|p| p.answer), run.part2.map(|p| p.answer)]);
+
+    let reference = pool::catch(|| entry.solver.reference(input, params))
+        .map_err(|failure| failure.to_string())
+        .and_then(|answers| answers.ok_or_else(|| String::from("no reference solver")))
+        .and_then(|answers| answers.map_err(|e| e.to_string()))
+        .map(|(part1, part2)| [keep(Some(part1), 1), keep(Some(part2), 2)]);
+
+    (optimized, reference)
+}
+
+/// Whether both solvers came to the same answers. Solvers that both reject
+/// an input agree, whatever their reasons.
+fn agree(optimized: &Outcome, reference: &Outcome) -> bool {
+    match (optimized, reference) {
+        (Ok(optimized), Ok(reference)) => optimized == reference,
+        (Err(_), Err(_)) => true,
+        _ => false,
+    }
+}
+
+fn describe(outcome: &Outcome) -> String {
+    match outcome {
+        Ok(answers) => {
+            let answers: Vec<String> = answers.iter()
+                .zip(1..)
+                .filter_map(|(answer, part)| answer.as_ref().map(|answer| format!("part {} = {}", part, answer)))
+                .collect();
+            answers.join(", ")
+        },
+        Err(message) => format!("error: {}", message),
+    }
+}
+
+/// Deletes lines from `input` for as long as `fails` still holds, first in
+/// large chunks and then one at a time.
+fn minimize(input: &str, fails: impl Fn(&str) -> bool) -> String {
+    let join = |lines: &[&str]| lines.iter().map(|line| format!("{}\n", line)).collect::<String>();
+
+    let mut lines: Vec<&str> = input.lines().collect();
+    let mut chunk = (lines.len() / 2).max(1);
+
+    loop {
+        let mut removed = false;
+        let mut start = 0;
+
+        while start < lines.len() && lines.len() > 1 {
+            let end = (start + chunk).min(lines.len());
+            let candidate: Vec<&str> = lines[..start].iter().chain(&lines[end..]).copied().collect();
+
+            if fails(&join(&candidate)) {
+                lines = candidate;
+                removed = true;
+            } else {
+                start += chunk;
+            }
+        }
+
+        if chunk == 1 && !removed {
+            return join(&lines);
+        }
+        chunk = (chunk / 2).max(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn agreement() {
+        let answers = |part1: u32, part2: u64| Ok([Some(Solution::from(part1)), Some(Solution::from(part2))]);
+
+        assert!(agree(&answers(13, 30), &Ok([Some(Solution::from(13usize)), Some(Solution::from(30usize))])));
+        assert!(!agree(&answers(13, 30), &answers(13, 31)));
+        assert!(!agree(&answers(13, 30), &Err(String::from("line 1: no '|' separator"))));
+        assert!(agree(&Err(String::from("PANIC: oops")), &Err(String::from("line 1: invalid game id"))));
+    }
+
+    #[test]
+    fn minimizing() {
+        let input: String = (1..=40).map(|n| format!("{}\n", n)).collect();
+
+        // Fails as long as both 7 and 23 are in, and anything over 30 is.
+        let fails = |input: &str| {
+            let numbers: Vec<u32> = input.lines().map(|line| line.parse().unwrap()).collect();
+            numbers.contains(&7) && numbers.contains(&23) && numbers.iter().any(|&n| n > 30)
+        };
+
+        let minimized = minimize(&input, fails);
+        assert!(fails(&minimized));
+        assert_eq!(minimized.lines().count(), 3);
+        assert!(minimized.starts_with("7\n23\n"));
+
+        assert_eq!(minimize("1\n", |_| true), "1\n");
+    }
+}
@@ -0,0 +1,781 @@
+use advent_2023::etc::day::Season;
+use advent_2023::etc::params::Params;
+use advent_2023::years;
+use crate::runner::format::Format;
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+
+pub const USAGE: &str = "\
+Usage: advent_2023 [OPTIONS] <DAYS>...
+       advent_2023 bench [BENCH OPTIONS] <DAYS>...
+       advent_2023 watch [WATCH OPTIONS] <DAY>
+       advent_2023 new-day [--year <YEAR>] [--name <TITLE>] <DAY>
+       advent_2023 gen [--year <YEAR>] [--seed <S>] [--size <N>] <DAY>
+       advent_2023 check [CHECK OPTIONS] <DAY>
+
+Days, given as arguments or with --day:
+  5             a single day
+  1-5           an inclusive range of days
+  all           every registered day
+  implemented   every day that has a solver
+
+Options:
+  -y, --year <YEAR>    the season to run (default 2023)
+  -d, --day <DAYS>     the days to run, same as giving them as arguments
+  -p, --part <1|2>     only report the given part
+  -i, --input <PATH>   read the puzzle input from PATH, or stdin for '-'
+                       (single day only, default input/YEAR/dayNN.txt,
+                       falling back to input/dayNN_input.txt for 2023)
+      --raw            don't normalize the input (BOM, CRLF, trailing
+                       whitespace and blank lines)
+      --param <K=V>    run the day with a setting, reading the value from a
+                       file for K=@PATH (single day only, repeatable)
+  -r, --repeat <N>     run each day N times and report the mean time
+  -j, --jobs <N>       run up to N days in parallel (default 1)
+  -t, --timeout <SECS> give up on a day that takes longer than SECS
+  -a, --answers <PATH> check answers against PATH (default answers.txt,
+                       not checked for --input unless given explicitly)
+      --record         write this run's answers into the answers file
+                       (needs --answers for --input or --param)
+  -f, --format <FMT>   text (default), json, csv or tsv
+  -q, --quiet          print only the answers (text format)
+  -l, --list           list the registered days and exit
+  -h, --help           print this help
+
+A day that panics or times out is reported as PANIC or TIMEOUT and the other
+days still run. Exit status: 0 if every day ran and matched its answers, 1 if
+an answer was wrong or a day failed with an error, 2 for usage errors and 3 if
+a day panicked or timed out.
+
+Bench options (and --year, --day, --part, --input, --raw, --param):
+      --warmup <N>         untimed runs before measuring (default 3)
+      --runs <N>           measure exactly N runs
+      --budget <SECONDS>   measure for SECONDS per day (default 1)
+      --threshold <PCT>    flag medians more than PCT% slower than the
+                           previous results (default 10)
+  -o, --output <PATH>      where results are saved and compared
+                           (default bench_output.txt)
+
+Watch options (and --year, --day, --part, --input, --raw, --param):
+  -e, --example <PATH>     also run the day on PATH
+      --interval <SECONDS> how often files are checked (default 0.5)
+
+New day options:
+      --name <TITLE>       the puzzle title (default: the registered one)
+
+Gen options, printing a random input for the day to stdout:
+      --seed <S>           the same seed always gives the same input
+                           (default 0)
+      --size <N>           roughly how many lines to generate (default 100)
+
+Check options (and --year, --day, --part, --input, --raw, --param), comparing
+a day's answers with its slow reference solver and shrinking the first input
+they disagree on:
+      --seed <S>           the seed of the first generated input (default 0)
+      --size <N>           the size of each generated input (default 100)
+      --cases <N>          how many inputs to generate (default 100), unless
+                           --input is given";
+
+pub const LAST_DAY: u8 = 25;
+
+#[derive(Debug, PartialEq)]
+pub enum Command {
+    Run(Options),
+    Bench(BenchOptions),
+    Watch(WatchOptions),
+    NewDay { year: u16, day: u8, name: Option<String> },
+    Gen(GenOptions),
+    Check(CheckOptions),
+    List(u16),
+    Help,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Options {
+    pub year: u16,
+    pub days: Vec<u8>,
+    pub part: Option<u8>,
+    pub input: Option<PathBuf>,
+    /// Hand the input to the solver exactly as read, without normalizing it.
+    pub raw: bool,
+    /// Settings for the day, see `Day::parse_with`.
+    pub params: Params,
+    pub repeat: u32,
+    pub jobs: usize,
+    pub timeout: Option<Duration>,
+    pub format: Format,
+    pub quiet: bool,
+    pub answers: Option<PathBuf>,
+    pub record: bool,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct BenchOptions {
+    pub year: u16,
+    pub days: Vec<u8>,
+    pub part: Option<u8>,
+    pub input: Option<PathBuf>,
+    pub raw: bool,
+    pub params: Params,
+    pub warmup: u32,
+    /// Measure exactly this many runs; otherwise run for `budget`.
+    pub runs: Option<u32>,
+    pub budget: Duration,
+    pub threshold: f64,
+    pub output: PathBuf,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct WatchOptions {
+    pub year: u16,
+    pub day: u8,
+    pub part: Option<u8>,
+    pub input: Option<PathBuf>,
+    pub raw: bool,
+    pub params: Params,
+    pub example: Option<PathBuf>,
+    pub interval: Duration,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct GenOptions {
+    pub year: u16,
+    pub day: u8,
+    pub seed: u64,
+    /// Roughly how many records (lines, games, cards...) to generate.
+    pub size: usize,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct CheckOptions {
+    pub year: u16,
+    pub day: u8,
+    pub part: Option<u8>,
+    /// Check this input instead of generated ones.
+    pub input: Option<PathBuf>,
+    pub raw: bool,
+    pub params: Params,
+    pub seed: u64,
+    pub size: usize,
+    pub cases: u32,
+}
+
+/// Parses the command-line arguments (without the program name). `seasons`
+/// are the years that can be chosen, whose registries the `all` and
+/// `implemented` keywords expand to.
+pub fn parse<I>(args: I, seasons: &[Season]) -> Result<Command, String>
+where
+    I: IntoIterator<Item = String>,
+{
+    let mut args = args.into_iter().peekable();
+
+    match args.peek().map(String::as_str) {
+        Some("bench") => {
+            args.next();
+            parse_bench(args, seasons)
+        },
+        Some("watch") => {
+            args.next();
+            parse_watch(args, seasons)
+        },
+        Some("new-day") => {
+            args.next();
+            parse_new_day(args, seasons)
+        },
+        Some("gen") => {
+            args.next();
+            parse_gen(args, seasons)
+        },
+        Some("check") => {
+            args.next();
+            parse_check(args, seasons)
+        },
+        _ => parse_run(args, seasons),
+    }
+}
+
+fn parse_run<I>(mut args: I, seasons: &[Season]) -> Result<Command, String>
+where
+    I: Iterator<Item = String>,
+{
+    let mut selection = Selection::default();
+    let mut repeat = 1;
+    let mut jobs = 1;
+    let mut timeout = None;
+    let mut format = Format::Text;
+    let mut quiet = false;
+    let mut answers = None;
+    let mut record = false;
+    let mut list = false;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(Command::Help),
+            "-l" | "--list" => list = true,
+            "-q" | "--quiet" => quiet = true,
+            "--record" => record = true,
+            "-a" | "--answers" => {
+                let value = value_for(&arg, args.next())?;
+                answers = Some(PathBuf::from(value));
+            },
+            "-r" | "--repeat" => {
+                let value = value_for(&arg, args.next())?;
+                repeat = parse_count(&value, "repeat count")?;
+            },
+            "-f" | "--format" => {
+                let value = value_for(&arg, args.next())?;
+                format = value.parse()?;
+            },
+            "-j" | "--jobs" => {
+                let value = value_for(&arg, args.next())?;
+                jobs = parse_count(&value, "job count")? as usize;
+            },
+            "-t" | "--timeout" => {
+                let value = value_for(&arg, args.next())?;
+                timeout = Some(parse_seconds(&value)?);
+            },
+            _ => selection.accept(arg, &mut args)?,
+        }
+    }
+
+    if list {
+        return Ok(Command::List(selection.season(seasons)?.year));
+    }
+
+    let Selected { year, days, part, input, raw, params } = selection.finish(seasons)?;
+
+    // Answers to a custom input or settings would overwrite the real ones.
+    if record && answers.is_none() && (input.is_some() || !params.is_empty()) {
+        return Err(String::from("--record with --input or --param needs an explicit --answers file"));
+    }
+
+    Ok(Command::Run(Options { year, days, part, input, raw, params, repeat, jobs, timeout, format, quiet, answers, record }))
+}
+
+fn parse_bench<I>(mut args: I, seasons: &[Season]) -> Result<Command, String>
+where
+    I: Iterator<Item = String>,
+{
+    let mut selection = Selection::default();
+    let mut warmup = 3;
+    let mut runs = None;
+    let mut budget = Duration::from_secs(1);
+    let mut threshold = 10.0;
+    let mut output = PathBuf::from("bench_output.txt");
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(Command::Help),
+            "--warmup" => {
+                let value = value_for(&arg, args.next())?;
+                warmup = value.parse()
+                    .map_err(|_| format!("not a valid warmup count: '{}'", value))?;
+            },
+            "--runs" => {
+                let value = value_for(&arg, args.next())?;
+                runs = Some(parse_count(&value, "run count")?);
+            },
+            "--budget" => {
+                let value = value_for(&arg, args.next())?;
+                budget = parse_seconds(&value)?;
+            },
+            "--threshold" => {
+                let value = value_for(&arg, args.next())?;
+                threshold = match value.trim_end_matches('%').parse::<f64>() {
+                    Ok(val) if val >= 0.0 => val,
+                    _ => return Err(format!("not a valid threshold: '{}'", value)),
+                };
+            },
+            "-o" | "--output" => {
+                let value = value_for(&arg, args.next())?;
+                output = PathBuf::from(value);
+            },
+            _ => selection.accept(arg, &mut args)?,
+        }
+    }
+
+    let Selected { year, days, part, input, raw, params } = selection.finish(seasons)?;
+
+    Ok(Command::Bench(BenchOptions { year, days, part, input, raw, params, warmup, runs, budget, threshold, output }))
+}
+
+fn parse_watch<I>(mut args: I, seasons: &[Season]) -> Result<Command, String>
+where
+    I: Iterator<Item = String>,
+{
+    let mut selection = Selection::default();
+    let mut example = None;
+    let mut interval = Duration::from_millis(500);
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(Command::Help),
+            "-e" | "--example" => {
+                let value = value_for(&arg, args.next())?;
+                example = Some(PathBuf::from(value));
+            },
+            "--interval" => {
+                let value = value_for(&arg, args.next())?;
+                interval = parse_seconds(&value)?;
+            },
+            _ => selection.accept(arg, &mut args)?,
+        }
+    }
+
+    let Selected { year, days, part, input, raw, params } = selection.finish(seasons)?;
+
+    let [day] = days[..] else {
+        return Err(String::from("watch takes a single day"));
+    };
+
+    Ok(Command::Watch(WatchOptions { year, day, part, input, raw, params, example, interval }))
+}
+
+fn parse_new_day<I>(mut args: I, seasons: &[Season]) -> Result<Command, String>
+where
+    I: Iterator<Item = String>,
+{
+    let mut selection = Selection::default();
+    let mut day = None;
+    let mut name = None;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(Command::Help),
+            "-y" | "--year" => selection.year = Some(parse_year(&value_for(&arg, args.next())?)?),
+            "--name" => name = Some(value_for(&arg, args.next())?),
+            flag if flag.starts_with('-') => return Err(format!("unknown option '{}'", flag)),
+            _ if day.is_some() => return Err(String::from("new-day takes a single day")),
+            value => day = Some(parse_day(value)?),
+        }
+    }
+
+    let day = day.ok_or_else(|| String::from("no day given"))?;
+    let year = selection.season(seasons)?.year;
+
+    Ok(Command::NewDay { year, day, name })
+}
+
+fn parse_gen<I>(mut args: I, seasons: &[Season]) -> Result<Command, String>
+where
+    I: Iterator<Item = String>,
+{
+    let mut selection = Selection::default();
+    let mut day = None;
+    let mut seed = 0;
+    let mut size = 100;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(Command::Help),
+            "-y" | "--year" => selection.year = Some(parse_year(&value_for(&arg, args.next())?)?),
+            "--seed" => seed = parse_seed(&value_for(&arg, args.next())?)?,
+            "--size" => size = parse_count(&value_for(&arg, args.next())?, "size")? as usize,
+            flag if flag.starts_with('-') => return Err(format!("unknown option '{}'", flag)),
+            _ if day.is_some() => return Err(String::from("gen takes a single day")),
+            value => day = Some(parse_day(value)?),
+        }
+    }
+
+    let day = day.ok_or_else(|| String::from("no day given"))?;
+    let year = selection.season(seasons)?.year;
+
+    Ok(Command::Gen(GenOptions { year, day, seed, size }))
+}
+
+fn parse_check<I>(mut args: I, seasons: &[Season]) -> Result<Command, String>
+where
+    I: Iterator<Item = String>,
+{
+    let mut selection = Selection::default();
+    let mut seed = 0;
+    let mut size = 100;
+    let mut cases = 100;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(Command::Help),
+            "--seed" => seed = parse_seed(&value_for(&arg, args.next())?)?,
+            "--size" => size = parse_count(&value_for(&arg, args.next())?, "size")? as usize,
+            "--cases" => cases = parse_count(&value_for(&arg, args.next())?, "number of cases")?,
+            _ => selection.accept(arg, &mut args)?,
+        }
+    }
+
+    let Selected { year, days, part, input, raw, params } = selection.finish(seasons)?;
+
+    let [day] = days[..] else {
+        return Err(String::from("check takes a single day"));
+    };
+
+    Ok(Command::Check(CheckOptions { year, day, part, input, raw, params, seed, size, cases }))
+}
+
+/// The year, days, part, input and parameters shared by every command, as
+/// given. Day specifications are only expanded once the year is known.
+#[derive(Default)]
+struct Selection {
+    year: Option<u16>,
+    specs: Vec<String>,
+    part: Option<u8>,
+    input: Option<PathBuf>,
+    raw: bool,
+    params: Params,
+}
+
+/// A `Selection` resolved against the chosen season.
+struct Selected {
+    year: u16,
+    days: Vec<u8>,
+    part: Option<u8>,
+    input: Option<PathBuf>,
+    raw: bool,
+    params: Params,
+}
+
+impl Selection {
+    /// Handles a day specification or one of the shared options, failing on
+    /// anything else.
+    fn accept<I>(&mut self, arg: String, args: &mut I) -> Result<(), String>
+    where
+        I: Iterator<Item = String>,
+    {
+        match arg.as_str() {
+            "-y" | "--year" => {
+                let value = value_for(&arg, args.next())?;
+                self.year = Some(parse_year(&value)?);
+            },
+            "-d" | "--day" => {
+                let value = value_for(&arg, args.next())?;
+                self.specs.push(value);
+            },
+            "-p" | "--part" => {
+                let value = value_for(&arg, args.next())?;
+                self.part = Some(parse_part(&value)?);
+            },
+            "--raw" => self.raw = true,
+            "--param" => {
+                let value = value_for(&arg, args.next())?;
+                let (key, value) = parse_param(&value)?;
+                self.params.set(key, value);
+            },
+            "-i" | "--input" => {
+                let value = value_for(&arg, args.next())?;
+                self.input = Some(PathBuf::from(value));
+            },
+            flag if flag.starts_with('-') && flag.len() > 1 => {
+                return Err(format!("unknown option '{}'", flag));
+            },
+            _ => self.specs.push(arg),
+        }
+
+        Ok(())
+    }
+
+    /// The chosen season, the default one unless `--year` was given.
+    fn season<'a>(&self, seasons: &'a [Season]) -> Result<&'a Season, String> {
+        let year = self.year.unwrap_or(years::DEFAULT);
+
+        seasons.iter()
+            .find(|season| season.year == year)
+            .ok_or_else(|| {
+                let known: Vec<String> = seasons.iter().map(|season| season.year.to_string()).collect();
+                format!("no solutions for {} (available: {})", year, known.join(", "))
+            })
+    }
+
+    fn finish(self, seasons: &[Season]) -> Result<Selected, String> {
+        let season = self.season(seasons)?;
+        let registered: Vec<u8> = season.days.iter()
+            .map(|entry| entry.day)
+            .collect();
+        let implemented: Vec<u8> = season.days.iter()
+            .filter(|entry| entry.solver.is_implemented())
+            .map(|entry| entry.day)
+            .collect();
+
+        let mut days = Vec::new();
+
+        for spec in &self.specs {
+            for day in parse_days(spec, &registered, &implemented)? {
+                if !days.contains(&day) {
+                    days.push(day);
+                }
+            }
+        }
+
+        if days.is_empty() {
+            return Err(String::from("no days given"));
+        }
+
+        if self.input.is_some() && days.len() > 1 {
+            return Err(String::from("--input can only be used with a single day"));
+        }
+
+        if !self.params.is_empty() && days.len() > 1 {
+            return Err(String::from("--param can only be used with a single day"));
+        }
+
+        Ok(Selected { year: season.year, days, part: self.part, input: self.input, raw: self.raw, params: self.params })
+    }
+}
+
+fn value_for(flag: &str, value: Option<String>) -> Result<String, String> {
+    value.ok_or_else(|| format!("{} needs a value", flag))
+}
+
+fn parse_part(value: &str) -> Result<u8, String> {
+    match value {
+        "1" => Ok(1),
+        "2" => Ok(2),
+        _ => Err(format!("not a valid part: '{}' (expected 1 or 2)", value)),
+    }
+}
+
+fn parse_count(value: &str, what: &str) -> Result<u32, String> {
+    match value.parse::<u32>() {
+        Ok(0) | Err(_) => Err(format!("not a valid {}: '{}'", what, value)),
+        Ok(val) => Ok(val),
+    }
+}
+
+/// Splits a `key=value` parameter, reading the value from a file for
+/// `key=@path`.
+fn parse_param(param: &str) -> Result<(&str, String), String> {
+    let (key, value) = param.split_once('=')
+        .filter(|(key, _)| !key.is_empty())
+        .ok_or_else(|| format!("not a valid parameter: '{}' (expected KEY=VALUE)", param))?;
+
+    match value.strip_prefix('@') {
+        Some(path) => fs::read_to_string(path)
+            .map(|contents| (key, contents))
+            .map_err(|e| format!("failed to read parameter '{}' from {}: {}", key, path, e)),
+        None => Ok((key, value.to_string())),
+    }
+}
+
+fn parse_seed(value: &str) -> Result<u64, String> {
+    value.parse().map_err(|_| format!("not a valid seed: '{}'", value))
+}
+
+fn parse_year(value: &str) -> Result<u16, String> {
+    match value.parse::<u16>() {
+        Ok(year) if year >= 2015 => Ok(year),
+        _ => Err(format!("not a valid year: '{}'", value)),
+    }
+}
+
+fn parse_seconds(value: &str) -> Result<Duration, String> {
+    match value.parse::<f64>() {
+        Ok(secs) if secs > 0.0 && secs.is_finite() => Ok(Duration::from_secs_f64(secs)),
+        _ => Err(format!("not a valid number of seconds: '{}'", value)),
+    }
+}
+
+/// Expands a single day specification (`5`, `1-5`, `all`, `implemented`)
+/// into the days it covers.
+fn parse_days(spec: &str, registered: &[u8], implemented: &[u8]) -> Result<Vec<u8>, String> {
+    match spec {
+        "all" => return Ok(registered.to_vec()),
+        "implemented" => return Ok(implemented.to_vec()),
+        _ => (),
+    }
+
+    match spec.split_once('-') {
+        Some((start, end)) => {
+            let start = parse_day(start)?;
+            let end = parse_day(end)?;
+
+            if start > end {
+                return Err(format!("not a valid day range: '{}'", spec));
+            }
+
+            Ok((start..=end).collect())
+        },
+        None => Ok(vec![parse_day(spec)?]),
+    }
+}
+
+fn parse_day(value: &str) -> Result<u8, String> {
+    match value.trim().parse::<u8>() {
+        Ok(day) if (1..=LAST_DAY).contains(&day) => Ok(day),
+        _ => Err(format!("not a valid day: '{}' (expected 1-{})", value, LAST_DAY)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(args: &[&str]) -> Result<Command, String> {
+        parse(args.iter().map(|a| a.to_string()), years::YEARS)
+    }
+
+    fn days(args: &[&str]) -> Vec<u8> {
+        match run(args) {
+            Ok(Command::Run(options)) => options.days,
+            other => panic!("unexpected parse result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn day_specs() {
+        assert_eq!(days(&["3"]), vec![3]);
+        assert_eq!(days(&["1-5"]), vec![1, 2, 3, 4, 5]);
+        assert_eq!(days(&["4", "2-4"]), vec![4, 2, 3]);
+        assert_eq!(days(&["implemented"]), vec![1, 2, 3, 4]);
+        assert_eq!(days(&["all"]).len(), 25);
+    }
+
+    #[test]
+    fn options() {
+        let command = run(&["-q", "--part", "2", "--repeat", "10", "--input", "foo.txt", "-t", "1.5", "--param", "a=b=c", "7"]);
+        let mut params = Params::new();
+        params.set("a", "b=c");
+
+        assert_eq!(command, Ok(Command::Run(Options {
+            year: 2023,
+            days: vec![7],
+            part: Some(2),
+            input: Some(PathBuf::from("foo.txt")),
+            raw: false,
+            params,
+            repeat: 10,
+            jobs: 1,
+            timeout: Some(Duration::from_millis(1500)),
+            format: Format::Text,
+            quiet: true,
+            answers: None,
+            record: false,
+        })));
+        assert_eq!(run(&["1", "--help"]), Ok(Command::Help));
+        assert_eq!(run(&["--list"]), Ok(Command::List(2023)));
+
+        assert!(run(&["1", "--input", "ex.txt", "--record"]).is_err());
+        assert!(run(&["1", "--param", "a=b", "--record"]).is_err());
+        assert!(run(&["1", "--input", "ex.txt", "--record", "-a", "ex_answers.txt"]).is_ok());
+    }
+
+    #[test]
+    fn errors() {
+        assert!(run(&[]).is_err());
+        assert!(run(&["0"]).is_err());
+        assert!(run(&["26"]).is_err());
+        assert!(run(&["5-1"]).is_err());
+        assert!(run(&["x"]).is_err());
+        assert!(run(&["1", "--part", "3"]).is_err());
+        assert!(run(&["1", "--repeat", "0"]).is_err());
+        assert!(run(&["1", "--timeout", "-1"]).is_err());
+        assert!(run(&["1", "--part"]).is_err());
+        assert!(run(&["1", "--bogus"]).is_err());
+        assert!(run(&["1", "2", "--input", "foo.txt"]).is_err());
+        assert!(run(&["bench", "1", "--repeat", "2"]).is_err());
+        assert!(run(&["bench", "1", "--budget", "0"]).is_err());
+    }
+
+    #[test]
+    fn bench() {
+        let command = run(&["bench", "1-2", "--raw", "--runs", "50", "--warmup", "0", "--threshold", "5%"]);
+
+        assert_eq!(command, Ok(Command::Bench(BenchOptions {
+            year: 2023,
+            days: vec![1, 2],
+            part: None,
+            input: None,
+            raw: true,
+            params: Params::new(),
+            warmup: 0,
+            runs: Some(50),
+            budget: Duration::from_secs(1),
+            threshold: 5.0,
+            output: PathBuf::from("bench_output.txt"),
+        })));
+    }
+
+    #[test]
+    fn watch() {
+        let command = run(&["watch", "-e", "examples/day01.txt", "--interval", "2", "-p", "1", "1"]);
+
+        assert_eq!(command, Ok(Command::Watch(WatchOptions {
+            year: 2023,
+            day: 1,
+            part: Some(1),
+            input: None,
+            raw: false,
+            params: Params::new(),
+            example: Some(PathBuf::from("examples/day01.txt")),
+            interval: Duration::from_secs(2),
+        })));
+        assert!(run(&["watch", "1-2"]).is_err());
+        assert!(run(&["watch"]).is_err());
+    }
+
+    #[test]
+    fn new_day() {
+        assert_eq!(run(&["new-day", "6"]), Ok(Command::NewDay { year: 2023, day: 6, name: None }));
+        assert_eq!(run(&["new-day", "--name", "Wait For It", "6"]),
+            Ok(Command::NewDay { year: 2023, day: 6, name: Some(String::from("Wait For It")) }));
+        assert!(run(&["new-day"]).is_err());
+        assert!(run(&["new-day", "6", "7"]).is_err());
+        assert!(run(&["new-day", "1-5"]).is_err());
+        assert!(run(&["new-day", "--year", "2019", "6"]).is_err());
+    }
+
+    #[test]
+    fn gen() {
+        assert_eq!(run(&["gen", "4"]), Ok(Command::Gen(GenOptions { year: 2023, day: 4, seed: 0, size: 100 })));
+        assert_eq!(run(&["gen", "--seed", "42", "--size", "500", "2"]),
+            Ok(Command::Gen(GenOptions { year: 2023, day: 2, seed: 42, size: 500 })));
+        assert!(run(&["gen"]).is_err());
+        assert!(run(&["gen", "1", "2"]).is_err());
+        assert!(run(&["gen", "--seed", "-1", "1"]).is_err());
+        assert!(run(&["gen", "--size", "0", "1"]).is_err());
+    }
+
+    #[test]
+    fn check() {
+        assert_eq!(run(&["check", "--cases", "20", "--seed", "5", "-p", "2", "3"]), Ok(Command::Check(CheckOptions {
+            year: 2023,
+            day: 3,
+            part: Some(2),
+            input: None,
+            raw: false,
+            params: Params::new(),
+            seed: 5,
+            size: 100,
+            cases: 20,
+        })));
+        assert!(run(&["check", "1-2"]).is_err());
+        assert!(run(&["check", "--cases", "0", "1"]).is_err());
+    }
+
+    #[test]
+    fn params() {
+        let path = std::env::temp_dir().join(format!("advent_params_{}", std::process::id()));
+        fs::write(&path, "uno = 1\n").unwrap();
+
+        let command = run(&["1", "--param", &format!("vocabulary=@{}", path.display()), "--param", "ignore-case=true"]);
+        fs::remove_file(&path).unwrap();
+
+        let Ok(Command::Run(options)) = command else {
+            panic!("not a run: {:?}", command);
+        };
+        assert_eq!(options.params.get("vocabulary"), Some("uno = 1\n"));
+        assert_eq!(options.params.get("ignore-case"), Some("true"));
+
+        assert!(run(&["1", "--param", "novalue"]).is_err());
+        assert!(run(&["1", "--param", "=value"]).is_err());
+        assert!(run(&["1", "--param", "vocabulary=@/no/such/file"]).is_err());
+        assert!(run(&["1-2", "--param", "ignore-case=true"]).is_err());
+    }
+
+    #[test]
+    fn years() {
+        assert_eq!(days(&["--year", "2023", "--day", "5"]), vec![5]);
+        assert_eq!(days(&["-d", "1-2", "3"]), vec![1, 2, 3]);
+        assert!(run(&["--year", "2019", "1"]).is_err());
+        assert!(run(&["--year", "99", "1"]).is_err());
+        assert!(run(&["--year", "2019", "--list"]).is_err());
+    }
+}
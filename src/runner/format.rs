@@ -0,0 +1,312 @@
+use crate::runner::answers::Verdict;
+use crate::runner::pool::Failure;
+use crate::runner::report::{self, DayReport, PartReport};
+use std::fmt::Write;
+use std::str::FromStr;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Format {
+    Text,
+    Json,
+    Csv,
+    Tsv,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            "csv" => Ok(Format::Csv),
+            "tsv" => Ok(Format::Tsv),
+            _ => Err(format!("not a valid format: '{}' (expected text, json, csv or tsv)", s)),
+        }
+    }
+}
+
+const COLUMNS: [&str; 12] = [
+    "day", "part", "answer", "type", "runs", "parse_ms", "part_ms", "status", "expected", "error", "summed_ms", "wall_clock_ms",
+];
+
+/// Writes day reports to stdout in the chosen format as they arrive.
+/// Machine-readable formats get one record per day and part, and a last one
+/// with the status `total` for the run's times.
+pub struct Output {
+    format: Format,
+    quiet: bool,
+    records: usize,
+}
+
+impl Output {
+    pub fn new(format: Format, quiet: bool) -> Self {
+        Output { format, quiet, records: 0 }
+    }
+
+    pub fn begin(&mut self) {
+        match self.format {
+            Format::Text => (),
+            Format::Json => println!("["),
+            Format::Csv => println!("{}", COLUMNS.join(",")),
+            Format::Tsv => println!("{}", COLUMNS.join("\t")),
+        }
+    }
+
+    pub fn day(&mut self, report: &DayReport) {
+        if self.format == Format::Text {
+            report::print_text(report, self.quiet);
+            return;
+        }
+
+        for part in &report.parts {
+            self.record(&fields(report, part));
+        }
+    }
+
+    /// Reports a day that panicked or timed out in place of its answers.
+    pub fn failure(&mut self, day: u8, failure: &Failure) {
+        match self.format {
+            Format::Text if self.quiet => eprintln!("day{:02}: {}", day, failure),
+            Format::Text => println!("\n=== Day {:02} ===\n  . {}", day, failure),
+            _ => {
+                let status = match failure {
+                    Failure::Panic(_) => "panic",
+                    Failure::Timeout(_) => "timeout",
+                };
+                self.record(&failure_fields(day, status, &failure.to_string()));
+            },
+        }
+    }
+
+    /// Reports a day that couldn't be solved, e.g. for a missing input or one
+    /// that didn't parse.
+    pub fn error(&mut self, day: u8, message: &str) {
+        match self.format {
+            Format::Text => eprintln!("error: {}", message),
+            _ => self.record(&failure_fields(day, "error", message)),
+        }
+    }
+
+    fn record(&mut self, fields: &[Field]) {
+        match self.format {
+            Format::Json => {
+                if self.records > 0 {
+                    println!(",");
+                }
+                print!("  {}", json_object(fields));
+            },
+            Format::Csv => println!("{}", join(fields, ",", csv_field)),
+            Format::Tsv => println!("{}", join(fields, "\t", tsv_field)),
+            Format::Text => unreachable!(),
+        }
+
+        self.records += 1;
+    }
+
+    /// Closes the output with the run's times: `summed_ms` adds up the time
+    /// of every day (the mean one with `--repeat`), so it only matches the
+    /// `wall_clock_ms` of the whole run with a single job.
+    pub fn finish(&mut self, summed_ms: f64, wall_clock_ms: f64, jobs: usize) {
+        match self.format {
+            Format::Text if self.quiet => (),
+            Format::Text => {
+                println!("Summed day time: {:.4} ms", summed_ms);
+                println!("Wall-clock time: {:.4} ms ({} {})", wall_clock_ms, jobs, if jobs == 1 { "job" } else { "jobs" });
+            },
+            _ => self.record(&total_fields(summed_ms, wall_clock_ms)),
+        }
+
+        if self.format == Format::Json {
+            println!();
+            println!("]");
+        }
+    }
+}
+
+/// A record field, kept typed so JSON can tell numbers from strings.
+enum Field {
+    Number(String),
+    Str(String),
+    Null,
+}
+
+fn fields(report: &DayReport, part: &PartReport) -> [Field; 12] {
+    let (status, expected) = match &part.verdict {
+        None => (Field::Null, Field::Null),
+        Some(Verdict::Correct) => (Field::Str(String::from("correct")), Field::Null),
+        Some(Verdict::Unknown) => (Field::Str(String::from("unknown")), Field::Null),
+        Some(Verdict::Wrong(expected)) => (Field::Str(String::from("wrong")), Field::Str(expected.to_string())),
+    };
+
+    [
+        Field::Number(report.day.to_string()),
+        Field::Number(part.part.to_string()),
+        // Kept as a string: 64- and 128-bit answers don't survive a JSON number.
+        Field::Str(part.answer.to_string()),
+        Field::Str(part.answer.kind().to_string()),
+        Field::Number(report.runs.to_string()),
+        Field::Number(format!("{:.6}", report.parse_ms)),
+        Field::Number(format!("{:.6}", part.ms)),
+        status,
+        expected,
+        Field::Null,
+        Field::Null,
+        Field::Null,
+    ]
+}
+
+fn failure_fields(day: u8, status: &str, error: &str) -> [Field; 12] {
+    [
+        Field::Number(day.to_string()),
+        Field::Null,
+        Field::Null,
+        Field::Null,
+        Field::Null,
+        Field::Null,
+        Field::Null,
+        Field::Str(String::from(status)),
+        Field::Null,
+        Field::Str(error.to_string()),
+        Field::Null,
+        Field::Null,
+    ]
+}
+
+fn total_fields(summed_ms: f64, wall_clock_ms: f64) -> [Field; 12] {
+    [
+        Field::Null,
+        Field::Null,
+        Field::Null,
+        Field::Null,
+        Field::Null,
+        Field::Null,
+        Field::Null,
+        Field::Str(String::from("total")),
+        Field::Null,
+        Field::Null,
+        Field::Number(format!("{:.6}", summed_ms)),
+        Field::Number(format!("{:.6}", wall_clock_ms)),
+    ]
+}
+
+fn join(fields: &[Field], separator: &str, escape: fn(&str) -> String) -> String {
+    fields.iter()
+        .map(|field| match field {
+            Field::Number(value) | Field::Str(value) => escape(value),
+            Field::Null => String::new(),
+        })
+        .collect::<Vec<_>>()
+        .join(separator)
+}
+
+fn json_object(fields: &[Field]) -> String {
+    let members: Vec<String> = COLUMNS.iter()
+        .zip(fields)
+        .map(|(name, field)| {
+            let value = match field {
+                Field::Number(value) => value.clone(),
+                Field::Str(value) => json_string(value),
+                Field::Null => String::from("null"),
+            };
+            format!("\"{}\": {}", name, value)
+        })
+        .collect();
+
+    format!("{{{}}}", members.join(", "))
+}
+
+fn json_string(value: &str) -> String {
+    let mut result = String::from("\"");
+
+    for c in value.chars() {
+        match c {
+            '"' => result.push_str("\\\""),
+            '\\' => result.push_str("\\\\"),
+            '\n' => result.push_str("\\n"),
+            '\r' => result.push_str("\\r"),
+            '\t' => result.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(result, "\\u{:04x}", c as u32);
+            },
+            c => result.push(c),
+        }
+    }
+
+    result.push('"');
+    result
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn tsv_field(value: &str) -> String {
+    value.replace('\\', "\\\\")
+        .replace('\t', "\\t")
+        .replace('\n', "\\n")
+        .replace('\r', "\\r")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use advent_2023::etc::solution::Solution;
+
+    fn report() -> DayReport {
+        DayReport {
+            day: 1,
+            runs: 1,
+            parse_ms: 0.5,
+            parse_allocations: None,
+            parts: vec![PartReport {
+                part: 2,
+                answer: Solution::Text(String::from("#.\"\n.#")),
+                ms: 0.25,
+                allocations: None,
+                verdict: Some(Verdict::Wrong(Solution::U64(5))),
+            }],
+            fastest_ms: 0.75,
+        }
+    }
+
+    #[test]
+    fn json() {
+        let report = report();
+        let record = json_object(&fields(&report, &report.parts[0]));
+
+        assert_eq!(record, "{\"day\": 1, \"part\": 2, \"answer\": \"#.\\\"\\n.#\", \"type\": \"Text\", \"runs\": 1, \
+            \"parse_ms\": 0.500000, \"part_ms\": 0.250000, \"status\": \"wrong\", \"expected\": \"5\", \"error\": null, \
+            \"summed_ms\": null, \"wall_clock_ms\": null}");
+
+        let record = json_object(&failure_fields(3, "panic", &Failure::Panic(String::from("oops")).to_string()));
+        assert_eq!(record, "{\"day\": 3, \"part\": null, \"answer\": null, \"type\": null, \"runs\": null, \
+            \"parse_ms\": null, \"part_ms\": null, \"status\": \"panic\", \"expected\": null, \"error\": \"PANIC: oops\", \
+            \"summed_ms\": null, \"wall_clock_ms\": null}");
+    }
+
+    #[test]
+    fn csv_and_tsv() {
+        let report = report();
+        let fields = fields(&report, &report.parts[0]);
+
+        assert_eq!(join(&fields, ",", csv_field), "1,2,\"#.\"\"\n.#\",Text,1,0.500000,0.250000,wrong,5,,,");
+        assert_eq!(join(&fields, "\t", tsv_field), "1\t2\t#.\"\\n.#\tText\t1\t0.500000\t0.250000\twrong\t5\t\t\t");
+
+        let fields = failure_fields(4, "error", "day04 line 1: no '|' separator");
+        assert_eq!(join(&fields, ",", csv_field), "4,,,,,,,error,,day04 line 1: no '|' separator,,");
+
+        let fields = total_fields(12.5, 4.25);
+        assert_eq!(join(&fields, ",", csv_field), ",,,,,,,total,,,12.500000,4.250000");
+    }
+
+    #[test]
+    fn parse_format() {
+        assert_eq!("json".parse(), Ok(Format::Json));
+        assert!("xml".parse::<Format>().is_err());
+    }
+}
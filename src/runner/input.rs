@@ -0,0 +1,86 @@
+use advent_2023::etc::day::Entry;
+use advent_2023::etc::input::normalize;
+use advent_2023::years;
+use std::fs::read_to_string;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+/// The path a day's input is read from when no `--input` is given:
+/// `input/<year>/dayNN.txt`, or the older `input/dayNN_input.txt` for the
+/// default season when only that one exists.
+pub fn default_path(year: u16, day: u8) -> PathBuf {
+    let path = PathBuf::from(format!("input/{}/day{:02}.txt", year, day));
+    let legacy = PathBuf::from(format!("input/day{:02}_input.txt", day));
+
+    if year == years::DEFAULT && !path.exists() && legacy.exists() {
+        legacy
+    } else {
+        path
+    }
+}
+
+/// Reads the puzzle input for `day`: from `explicit` if given (`-` meaning
+/// stdin), otherwise from the day's default path. Unless `raw`, the input is
+/// normalized before it's handed to the solver.
+pub fn load(year: u16, day: u8, explicit: Option<&Path>, raw: bool) -> Result<String, String> {
+    let contents = read(year, day, explicit)?;
+
+    if raw {
+        Ok(contents)
+    } else {
+        Ok(normalize(&contents))
+    }
+}
+
+fn read(year: u16, day: u8, explicit: Option<&Path>) -> Result<String, String> {
+    match explicit {
+        Some(path) if path == Path::new("-") => {
+            let mut contents = String::new();
+            io::stdin().read_to_string(&mut contents)
+                .map_err(|e| format!("failed to read input for day {} from stdin: {}", day, e))?;
+            Ok(contents)
+        },
+        Some(path) => read_to_string(path)
+            .map_err(|e| format!("failed to read input for day {} from {}: {}", day, path.display(), e)),
+        None => {
+            let path = default_path(year, day);
+
+            if !path.exists() {
+                return Err(format!("missing input for day {}, expected at {}", day, path.display()));
+            }
+
+            read_to_string(&path)
+                .map_err(|e| format!("failed to read input for day {} from {}: {}", day, path.display(), e))
+        },
+    }
+}
+
+/// Looks up the solver for `day` and loads its input. Stubs whose default
+/// input doesn't exist get an empty one, since they only ever answer
+/// `pending`; an explicit input must still be readable.
+pub fn resolve(year: u16, day: u8, explicit: Option<&Path>, raw: bool) -> Result<(&'static Entry, String), String> {
+    let entry = years::get(year)
+        .and_then(|season| season.get(day))
+        .ok_or_else(|| format!("no solver registered for day {} of {}", day, year))?;
+
+    if explicit.is_none() && !entry.solver.is_implemented() && !default_path(year, day).exists() {
+        return Ok((entry, String::new()));
+    }
+
+    Ok((entry, load(year, day, explicit, raw)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stub_inputs() {
+        let (entry, contents) = resolve(years::DEFAULT, 25, None, false).unwrap();
+        assert!(!entry.solver.is_implemented());
+        assert_eq!(contents, "");
+
+        let missing = resolve(years::DEFAULT, 25, Some(Path::new("input/nonexistent.txt")), false);
+        assert!(missing.err().unwrap().contains("nonexistent.txt"));
+    }
+}
@@ -0,0 +1,10 @@
+pub mod answers;
+pub mod bench;
+pub mod check;
+pub mod cli;
+pub mod format;
+pub mod input;
+pub mod pool;
+pub mod report;
+pub mod scaffold;
+pub mod watch;
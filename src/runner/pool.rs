@@ -0,0 +1,196 @@
+use std::any::Any;
+use std::cell::{Cell, RefCell};
+use std::collections::BTreeMap;
+use std::fmt::{self, Display, Formatter};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{mpsc, Arc};
+use std::thread;
+use std::time::Duration;
+
+/// Why an item produced no result.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Failure {
+    Panic(String),
+    Timeout(Duration),
+}
+
+impl Display for Failure {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Failure::Panic(message) => write!(f, "PANIC: {}", message),
+            Failure::Timeout(limit) => write!(f, "TIMEOUT after {:?}", limit),
+        }
+    }
+}
+
+thread_local! {
+    static CATCHING: Cell<bool> = const { Cell::new(false) };
+    static LOCATION: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// Replaces the default panic output for panics inside pool items, which are
+/// reported as a `Failure` instead; their location is kept for the message.
+/// Panics anywhere else still go to the previous hook.
+pub fn capture_panics() {
+    let previous = panic::take_hook();
+
+    panic::set_hook(Box::new(move |info| {
+        if CATCHING.get() {
+            LOCATION.set(info.location().map(ToString::to_string));
+        } else {
+            previous(info);
+        }
+    }));
+}
+
+/// Runs `work` on every item using `jobs` worker threads and hands each
+/// result to `report` in the order of `items`, as soon as it and everything
+/// before it is done. A panicking item is reported as a `Failure` instead of
+/// taking the other items down with it.
+///
+/// With a `timeout`, each item runs on a thread of its own and is reported
+/// as timed out once it exceeds it. There's no way to stop a thread, so a
+/// timed out item keeps running in the background until the process exits.
+pub fn run_ordered<T, R, W, F>(jobs: usize, items: &[T], timeout: Option<Duration>, work: W, mut report: F)
+where
+    T: Clone + Send + Sync + 'static,
+    R: Send + 'static,
+    W: Fn(&T) -> R + Send + Sync + 'static,
+    F: FnMut(&T, Result<R, Failure>),
+{
+    let shared: Arc<[T]> = items.into();
+    let work = Arc::new(work);
+    let next = Arc::new(AtomicUsize::new(0));
+    let (sender, receiver) = mpsc::channel();
+
+    // Plain threads rather than scoped ones: a scope would wait for items
+    // that timed out.
+    for _ in 0..jobs.clamp(1, items.len().max(1)) {
+        let sender = sender.clone();
+        let items = Arc::clone(&shared);
+        let next = Arc::clone(&next);
+        let work = Arc::clone(&work);
+
+        thread::spawn(move || loop {
+            let idx = next.fetch_add(1, Ordering::Relaxed);
+            let Some(item) = items.get(idx) else {
+                break;
+            };
+
+            let result = match timeout {
+                None => catch(|| work(item)),
+                Some(limit) => {
+                    let (done, outcome) = mpsc::channel();
+                    let work = Arc::clone(&work);
+                    let item = item.clone();
+
+                    thread::spawn(move || {
+                        let _ = done.send(catch(|| work(&item)));
+                    });
+
+                    match outcome.recv_timeout(limit) {
+                        Ok(result) => result,
+                        Err(mpsc::RecvTimeoutError::Timeout) => Err(Failure::Timeout(limit)),
+                        Err(mpsc::RecvTimeoutError::Disconnected) => {
+                            Err(Failure::Panic(String::from("worker thread died")))
+                        },
+                    }
+                },
+            };
+
+            if sender.send((idx, result)).is_err() {
+                break;
+            }
+        });
+    }
+    drop(sender);
+
+    let mut pending = BTreeMap::new();
+    let mut expected = 0;
+
+    for (idx, result) in receiver {
+        pending.insert(idx, result);
+
+        while let Some(result) = pending.remove(&expected) {
+            report(&items[expected], result);
+            expected += 1;
+        }
+    }
+}
+
+/// Runs `f` on the current thread, turning a panic into a `Failure`. The
+/// panic is only kept from being printed once `capture_panics` was called.
+pub fn catch<R>(f: impl FnOnce() -> R) -> Result<R, Failure> {
+    CATCHING.set(true);
+    let result = panic::catch_unwind(AssertUnwindSafe(f));
+    CATCHING.set(false);
+
+    result.map_err(|payload| {
+        let message = panic_message(payload);
+
+        match LOCATION.take() {
+            Some(location) => Failure::Panic(format!("{} at {}", message, location)),
+            None => Failure::Panic(message),
+        }
+    })
+}
+
+/// Extracts the message a panic was raised with.
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        String::from("unknown panic")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn results_in_order() {
+        let items: Vec<u64> = (0..20).collect();
+        let mut seen = Vec::new();
+
+        run_ordered(4, &items, None, |&i| {
+            thread::sleep(Duration::from_millis(20 - i));
+            i * 2
+        }, |&i, result| seen.push((i, result)));
+
+        let expected: Vec<_> = items.iter().map(|&i| (i, Ok(i * 2))).collect();
+        assert_eq!(seen, expected);
+    }
+
+    #[test]
+    fn panics_are_isolated() {
+        let items = [1, 2, 3];
+        let mut seen = Vec::new();
+
+        run_ordered(2, &items, None, |&i| {
+            if i == 2 {
+                panic!("day {} exploded", i);
+            }
+            i
+        }, |&i, result| seen.push((i, result)));
+
+        assert_eq!(seen, vec![(1, Ok(1)), (2, Err(Failure::Panic(String::from("day 2 exploded")))), (3, Ok(3))]);
+    }
+
+    #[test]
+    fn timeouts() {
+        let items = [1, 200, 3];
+        let mut seen = Vec::new();
+        let limit = Duration::from_millis(50);
+
+        run_ordered(1, &items, Some(limit), |&i| {
+            thread::sleep(Duration::from_millis(i));
+            i
+        }, |&i, result| seen.push((i, result)));
+
+        assert_eq!(seen, vec![(1, Ok(1)), (200, Err(Failure::Timeout(limit))), (3, Ok(3))]);
+    }
+}
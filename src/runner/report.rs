@@ -0,0 +1,135 @@
+use advent_2023::etc::alloc::AllocStats;
+use advent_2023::etc::day::{PartRun, Run};
+use advent_2023::etc::solution::Solution;
+use crate::runner::answers::{self, Verdict};
+
+/// One part's answer with its mean time over all runs.
+pub struct PartReport {
+    pub part: u8,
+    pub answer: Solution,
+    pub ms: f64,
+    /// What the last run allocated, when allocations are counted.
+    pub allocations: Option<AllocStats>,
+    /// Set when the answer was checked against an answers file.
+    pub verdict: Option<Verdict>,
+}
+
+/// What the runner prints for a day: the answers and per-phase timings,
+/// averaged over every repeated run.
+pub struct DayReport {
+    pub day: u8,
+    pub runs: usize,
+    pub parse_ms: f64,
+    pub parse_allocations: Option<AllocStats>,
+    pub parts: Vec<PartReport>,
+    pub fastest_ms: f64,
+}
+
+impl DayReport {
+    /// Summarises repeated runs of a day. `runs` must not be empty.
+    pub fn from_runs(day: u8, runs: Vec<Run>) -> Self {
+        let count = runs.len();
+        let mean = |ms: f64| ms / count as f64;
+
+        let parse_ms = mean(runs.iter().map(|run| run.parse.as_millis()).sum());
+        let fastest_ms = runs.iter()
+            .map(|run| run.parse.as_millis() + part_ms(&run.part1) + part_ms(&run.part2))
+            .fold(f64::MAX, f64::min);
+
+        let part1_ms = mean(runs.iter().map(|run| part_ms(&run.part1)).sum());
+        let part2_ms = mean(runs.iter().map(|run| part_ms(&run.part2)).sum());
+
+        let last = runs.into_iter().last().expect("at least one run");
+        let mut parts = Vec::new();
+
+        if let Some(part) = last.part1 {
+            let allocations = part.measurement.allocations;
+            parts.push(PartReport { part: 1, answer: part.answer, ms: part1_ms, allocations, verdict: None });
+        }
+        if let Some(part) = last.part2 {
+            let allocations = part.measurement.allocations;
+            parts.push(PartReport { part: 2, answer: part.answer, ms: part2_ms, allocations, verdict: None });
+        }
+
+        let parse_allocations = last.parse.allocations;
+
+        DayReport { day, runs: count, parse_ms, parse_allocations, parts, fastest_ms }
+    }
+
+    /// Mean time of a whole run: parsing plus every part that was run.
+    pub fn elapsed_ms(&self) -> f64 {
+        self.parse_ms + self.parts.iter().map(|part| part.ms).sum::<f64>()
+    }
+
+    /// Allocations of a whole run, with the peak of the hungriest phase.
+    pub fn allocations(&self) -> Option<AllocStats> {
+        let mut total = self.parse_allocations?;
+
+        for stats in self.parts.iter().filter_map(|part| part.allocations) {
+            total.allocations += stats.allocations;
+            total.bytes += stats.bytes;
+            total.peak = total.peak.max(stats.peak);
+        }
+
+        Some(total)
+    }
+}
+
+fn part_ms(part: &Option<PartRun>) -> f64 {
+    part.as_ref().map_or(0.0, |part| part.measurement.as_millis())
+}
+
+/// Prints a day in the runner's human-readable layout.
+pub fn print_text(report: &DayReport, quiet: bool) {
+    if quiet {
+        for part in &report.parts {
+            println!("{}", part.answer);
+        }
+        return;
+    }
+
+    println!("\n=== Day {:02} ===", report.day);
+
+    for part in &report.parts {
+        print_answer(part);
+    }
+
+    println!("  . Parse: {:.4} ms{}", report.parse_ms, describe_allocations(report.parse_allocations));
+
+    let allocations = describe_allocations(report.allocations());
+
+    if report.runs > 1 {
+        println!("  . Elapsed: {:.4} ms (mean of {} runs, min {:.4} ms){}",
+            report.elapsed_ms(), report.runs, report.fastest_ms, allocations);
+    } else {
+        println!("  . Elapsed: {:.4} ms{}", report.elapsed_ms(), allocations);
+    }
+}
+
+/// Formats allocation counts to follow a time, or nothing if they weren't
+/// counted.
+fn describe_allocations(allocations: Option<AllocStats>) -> String {
+    allocations.map(|stats| format!(" ({})", stats)).unwrap_or_default()
+}
+
+/// Prints a part's answer, verdict and time, putting multi-line answers on
+/// their own lines.
+fn print_answer(part: &PartReport) {
+    let verdict = part.verdict.as_ref()
+        .map(|verdict| format!(" {}", answers::describe(verdict)))
+        .unwrap_or_default();
+
+    let allocations = part.allocations
+        .map(|stats| format!(", {}", stats))
+        .unwrap_or_default();
+
+    match &part.answer {
+        Solution::Text(text) => {
+            println!("  . Part {}:{} ({:.4} ms{})", part.part, verdict, part.ms, allocations);
+            for line in text.lines() {
+                println!("      {}", line);
+            }
+        },
+        answer => println!("  . Part {}: {}{} ({:.4} ms{})", part.part, answer, verdict, part.ms, allocations),
+    }
+}
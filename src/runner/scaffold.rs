@@ -0,0 +1,168 @@
+use advent_2023::etc::day::Season;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// The directory a season's day modules live in.
+pub fn source_dir(season: &Season) -> PathBuf {
+    Path::new("src").join(season.module)
+}
+
+/// Renders a new day module: a registered stub with both parts pending and
+/// an example test ready to be filled in.
+pub fn render(day: u8, name: &str) -> String {
+    format!(r#"use crate::Solution;
+use crate::etc::day::Day;
+use crate::etc::error::AocResult;
+
+pub struct Day{day:02};
+
+impl Day for Day{day:02} {{
+    type Input<'a> = &'a str;
+
+    fn name(&self) -> &'static str {{
+        "{name}"
+    }}
+
+    fn is_implemented(&self) -> bool {{
+        false
+    }}
+
+    fn parse<'a>(&self, input: &'a str) -> AocResult<Self::Input<'a>> {{
+        Ok(input)
+    }}
+
+    fn part1(&self, _input: &Self::Input<'_>) -> AocResult<Solution> {{
+        Ok(Solution::Pending)
+    }}
+
+    fn part2(&self, _input: &Self::Input<'_>) -> AocResult<Solution> {{
+        Ok(Solution::Pending)
+    }}
+}}
+
+#[cfg(test)]
+mod tests {{
+    use super::*;
+
+    crate::examples! {{
+        Day{day:02};
+        example: "" => part1 = Solution::Pending, part2 = Solution::Pending;
+    }}
+}}"#, day = day, name = name.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Whether `contents` is an untouched module generated by `render`.
+fn is_stub(contents: &str, day: u8) -> bool {
+    let name = contents.split_once("fn name(&self) -> &'static str {")
+        .and_then(|(_, rest)| rest.split_once('"'))
+        .and_then(|(_, rest)| rest.split_once("\"\n"))
+        .map(|(name, _)| name.replace("\\\"", "\"").replace("\\\\", "\\"));
+
+    name.is_some_and(|name| contents == render(day, &name))
+}
+
+/// Adds `day` to the `register_days!` list in `registry`, keeping the list in
+/// order. Returns `None` if the day is already registered.
+fn register(registry: &str, day: u8) -> Option<String> {
+    let entry = format!("    {} => day{:02}::Day{:02},", day, day, day);
+    let entry_day = |line: &str| line.trim().split_once(" =>").and_then(|(d, _)| d.parse::<u8>().ok());
+
+    let mut lines: Vec<&str> = registry.lines().collect();
+
+    if lines.iter().any(|line| entry_day(line) == Some(day)) {
+        return None;
+    }
+
+    let position = lines.iter()
+        .position(|line| entry_day(line).is_some_and(|d| d > day))
+        .or_else(|| lines.iter().rposition(|line| line.trim() == "}"))?;
+    lines.insert(position, &entry);
+
+    let mut result = lines.join("\n");
+    if registry.ends_with('\n') {
+        result.push('\n');
+    }
+
+    Some(result)
+}
+
+/// Generates `dayNN.rs` in `dir` and registers it in `dir/mod.rs`, refusing
+/// to overwrite a module that has more than the generated stub in it.
+/// Returns what was done, one line per change.
+pub fn new_day(dir: &Path, season: &Season, day: u8, name: Option<&str>) -> Result<Vec<String>, String> {
+    let path = dir.join(format!("day{:02}.rs", day));
+    let registry_path = dir.join("mod.rs");
+    let mut changes = Vec::new();
+
+    let name = name.map(String::from)
+        .or_else(|| season.get(day).map(|entry| entry.solver.name().to_string()))
+        .unwrap_or_else(|| format!("Day {}", day));
+
+    if let Ok(existing) = fs::read_to_string(&path) {
+        if !is_stub(&existing, day) {
+            return Err(format!("{} already has code in it, refusing to overwrite it", path.display()));
+        }
+    }
+
+    let registry = fs::read_to_string(&registry_path)
+        .map_err(|e| format!("failed to read {}: {}", registry_path.display(), e))?;
+
+    fs::write(&path, render(day, &name))
+        .map_err(|e| format!("failed to write {}: {}", path.display(), e))?;
+    changes.push(format!("wrote {}", path.display()));
+
+    if let Some(registry) = register(&registry, day) {
+        fs::write(&registry_path, registry)
+            .map_err(|e| format!("failed to write {}: {}", registry_path.display(), e))?;
+        changes.push(format!("registered day {} in {}", day, registry_path.display()));
+    }
+
+    Ok(changes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use advent_2023::years;
+
+    #[test]
+    fn stubs() {
+        let stub = render(7, "Camel \"Cards\"");
+
+        assert!(is_stub(&stub, 7));
+        assert!(!is_stub(&stub, 8));
+        assert!(!is_stub(&stub.replace("Ok(Solution::Pending)", "Ok(Solution::from(5))"), 7));
+        assert!(!is_stub("", 7));
+    }
+
+    #[test]
+    fn registry() {
+        let registry = "crate::register_days! {\n    1 => day01::Day01,\n    3 => day03::Day03,\n}";
+
+        assert_eq!(register(registry, 2).unwrap(),
+            "crate::register_days! {\n    1 => day01::Day01,\n    2 => day02::Day02,\n    3 => day03::Day03,\n}");
+        assert_eq!(register(registry, 4).unwrap(),
+            "crate::register_days! {\n    1 => day01::Day01,\n    3 => day03::Day03,\n    4 => day04::Day04,\n}");
+        assert_eq!(register(registry, 3), None);
+    }
+
+    #[test]
+    fn new_day_in_directory() {
+        let dir = std::env::temp_dir().join(format!("advent_scaffold_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("mod.rs"), "crate::register_days! {\n}").unwrap();
+
+        let season = years::get(years::DEFAULT).unwrap();
+
+        let changes = new_day(&dir, season, 9, Some("Mirage Maintenance")).unwrap();
+        assert_eq!(changes.len(), 2);
+        assert_eq!(fs::read_to_string(dir.join("day09.rs")).unwrap(), render(9, "Mirage Maintenance"));
+
+        // Regenerating an untouched stub is fine, overwriting real code isn't.
+        assert_eq!(new_day(&dir, season, 9, None).unwrap().len(), 1);
+        fs::write(dir.join("day09.rs"), "fn solved() {}").unwrap();
+        assert!(new_day(&dir, season, 9, None).is_err());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,179 @@
+use advent_2023::etc::day::Entry;
+use advent_2023::years;
+use advent_2023::etc::solution::Solution;
+use crate::runner::cli::WatchOptions;
+use crate::runner::input;
+use crate::runner::pool;
+use crate::runner::report::{self, DayReport, PartReport};
+use crate::runner::scaffold;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::SystemTime;
+
+const CLEAR_SCREEN: &str = "\x1b[2J\x1b[H";
+
+/// A file whose modification time is polled.
+struct Watched {
+    path: PathBuf,
+    modified: Option<SystemTime>,
+}
+
+impl Watched {
+    fn new(path: PathBuf) -> Self {
+        let modified = modified(&path);
+        Watched { path, modified }
+    }
+
+    /// Whether the file changed, appeared or disappeared since the last call.
+    fn poll(&mut self) -> bool {
+        let modified = modified(&self.path);
+        let changed = modified != self.modified;
+        self.modified = modified;
+        changed
+    }
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|metadata| metadata.modified()).ok()
+}
+
+/// Re-runs a day every time its input or example file changes, until the
+/// process is interrupted. Only returns (with false) if the day can't be
+/// watched at all.
+pub fn run(options: &WatchOptions) -> bool {
+    let Some(season) = years::get(options.year) else {
+        eprintln!("error: no solutions for {}", options.year);
+        return false;
+    };
+    let Some(entry) = season.get(options.day) else {
+        eprintln!("error: no solver registered for day {} of {}", options.day, options.year);
+        return false;
+    };
+
+    let input = options.input.clone().unwrap_or_else(|| input::default_path(options.year, options.day));
+    let mut inputs = vec![Watched::new(input)];
+    inputs.extend(options.example.iter().cloned().map(Watched::new));
+
+    // The binary can't reload a solver, so an edited source only gets a note.
+    let mut source = Watched::new(scaffold::source_dir(season).join(format!("day{:02}.rs", options.day)));
+    let mut source_changed = false;
+
+    let mut previous: Vec<Option<Vec<PartReport>>> = inputs.iter().map(|_| None).collect();
+    let mut first = true;
+
+    // A panicking solver is reported like an error, not over the screen.
+    pool::capture_panics();
+
+    loop {
+        let mut changed: Vec<PathBuf> = inputs.iter_mut()
+            .filter_map(|watched| watched.poll().then(|| watched.path.clone()))
+            .collect();
+
+        if source.poll() {
+            source_changed = true;
+            changed.push(source.path.clone());
+        }
+
+        if first || !changed.is_empty() {
+            print!("{}", CLEAR_SCREEN);
+            println!("Watching day {} ({}), Ctrl-C to stop", options.day, entry.solver.name());
+
+            if !first {
+                let changed: Vec<String> = changed.iter().map(|path| path.display().to_string()).collect();
+                println!("Changed: {}", changed.join(", "));
+            }
+            if source_changed {
+                println!("note: {} changed, rebuild and restart to pick up the new solver", source.path.display());
+            }
+
+            for (watched, previous) in inputs.iter().zip(previous.iter_mut()) {
+                println!("\n--- {} ---", watched.path.display());
+
+                match solve(entry, options, &watched.path) {
+                    Ok(report) => {
+                        report::print_text(&report, false);
+
+                        if let Some(previous) = previous.as_ref() {
+                            for line in diff(previous, &report.parts) {
+                                println!("  . {}", line);
+                            }
+                        }
+                        *previous = Some(report.parts);
+                    },
+                    Err(message) => println!("error: {}", message),
+                }
+            }
+
+            first = false;
+        }
+
+        thread::sleep(options.interval);
+    }
+}
+
+/// Runs the day once on the file at `path`, turning a panic into an error.
+fn solve(entry: &Entry, options: &WatchOptions, path: &Path) -> Result<DayReport, String> {
+    let contents = input::load(options.year, options.day, Some(path), options.raw)?;
+    let day = options.day;
+
+    pool::catch(|| entry.solver.run_with(&contents, options.part, &options.params))
+        .map_err(|failure| failure.to_string())?
+        .map(|run| DayReport::from_runs(day, vec![run]))
+        .map_err(|e| e.in_day(day).to_string())
+}
+
+/// Describes how the answers changed since the previous run.
+fn diff(previous: &[PartReport], current: &[PartReport]) -> Vec<String> {
+    let answer = |parts: &[PartReport], part: u8| -> Option<Solution> {
+        parts.iter().find(|p| p.part == part).map(|p| p.answer.clone())
+    };
+
+    let mut changes = Vec::new();
+
+    for part in [1, 2] {
+        match (answer(previous, part), answer(current, part)) {
+            (Some(before), Some(after)) if before != after => {
+                changes.push(format!("Part {} changed: {} -> {}", part, before, after));
+            },
+            (Some(_), Some(_)) => changes.push(format!("Part {} unchanged", part)),
+            _ => (),
+        }
+    }
+
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parts(answers: &[Solution]) -> Vec<PartReport> {
+        answers.iter()
+            .zip(1..)
+            .map(|(answer, part)| PartReport { part, answer: answer.clone(), ms: 0.0, allocations: None, verdict: None })
+            .collect()
+    }
+
+    #[test]
+    fn answer_diff() {
+        let before = parts(&[Solution::U32(142), Solution::U32(281)]);
+        let after = parts(&[Solution::U64(142), Solution::U32(280)]);
+
+        assert_eq!(diff(&before, &after), vec!["Part 1 unchanged", "Part 2 changed: 281 -> 280"]);
+        assert!(diff(&before, &parts(&[])).is_empty());
+    }
+
+    #[test]
+    fn polling() {
+        let path = std::env::temp_dir().join(format!("advent_watch_{}", std::process::id()));
+        let mut watched = Watched::new(path.clone());
+
+        assert!(!watched.poll());
+        fs::write(&path, "1").unwrap();
+        assert!(watched.poll());
+        assert!(!watched.poll());
+        fs::remove_file(&path).unwrap();
+        assert!(watched.poll());
+    }
+}
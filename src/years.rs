@@ -0,0 +1,17 @@
+use crate::days;
+use crate::etc::day::Season;
+
+/// The season used when no year is given. Its days live in the `days`
+/// module, which predates the other seasons.
+pub const DEFAULT: u16 = 2023;
+
+/// Every season with a registry, oldest first. A new season gets a module of
+/// its own with a `register_days!` list and an entry here.
+pub static YEARS: &[Season] = &[
+    Season { year: 2023, module: "days", days: days::DAYS },
+];
+
+/// Looks up the season for `year`.
+pub fn get(year: u16) -> Option<&'static Season> {
+    YEARS.iter().find(|season| season.year == year)
+}
@@ -0,0 +1,144 @@
+use advent_2023::days::{self, day01, day02, day03, day04};
+use advent_2023::etc::day::Day;
+use advent_2023::etc::params::Params;
+use advent_2023::Solution;
+
+const DAY02: &str = "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
+Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
+Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
+Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
+Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green";
+
+const DAY03: &str = "467..114..
+...*......
+..35..633.
+......#...
+617*......
+.....+.58.
+..592.....
+......755.
+...$.*....
+.664.598..";
+
+const DAY04: &str = "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
+Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
+Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
+Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
+Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
+Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11";
+
+/// Runs a registered day through the registry, the way the runner does.
+fn solve(day: u8, input: &str) -> (Solution, Solution) {
+    let entry = days::get(day).expect("day is registered");
+    let run = entry.solver.run(input, None).expect("example solves");
+
+    (run.part1.unwrap().answer, run.part2.unwrap().answer)
+}
+
+#[test]
+fn registry() {
+    let registered: Vec<u8> = days::DAYS.iter().map(|entry| entry.day).collect();
+
+    assert_eq!(registered, (1..=25).collect::<Vec<u8>>());
+    assert_eq!(days::get(3).unwrap().solver.name(), "Gear Ratios");
+    assert!(!days::get(25).unwrap().solver.is_implemented());
+    assert!(!days::get(25).unwrap().solver.has_reference());
+    assert!(days::get(26).is_none());
+}
+
+#[test]
+fn solves_examples() {
+    assert_eq!(solve(2, DAY02), (Solution::from(8u32), Solution::from(2286u32)));
+    assert_eq!(solve(3, DAY03), (Solution::from(4361usize), Solution::from(467835usize)));
+    assert_eq!(solve(4, DAY04), (Solution::from(13usize), Solution::from(30usize)));
+    assert_eq!(solve(25, ""), (Solution::Pending, Solution::Pending));
+}
+
+#[test]
+fn single_part() {
+    let run = days::get(1).unwrap().solver.run("two1nine\n4nineeightseven2", Some(2)).unwrap();
+
+    assert!(run.part1.is_none());
+    assert_eq!(run.part2.unwrap().answer, Solution::from(71u32));
+}
+
+#[test]
+fn day_helpers() {
+    assert_eq!(day01::get_outside_digits("pqr3stu8vwx"), (3, 8));
+    assert_eq!(day01::get_outside_digits_including_words("xtwone3four"), (2, 4));
+
+    let (id, game) = day02::parse_line("Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green").unwrap();
+    assert_eq!(id, 3);
+    assert_eq!(game, day02::Game::from([("red", 20), ("green", 13), ("blue", 6)]));
+    assert!(!day02::Game::from(day02::BAG).can_fit(&game));
+
+    let schematic = day03::parse_schematic(DAY03).unwrap();
+    assert_eq!(schematic.part_number_sum().unwrap(), 4361);
+
+    let wins = day04::parse_cards(DAY04).unwrap();
+    assert_eq!(wins, vec![4, 2, 2, 1, 0, 0]);
+    assert_eq!(day04::calculate_points(&wins), 13);
+}
+
+#[test]
+fn days_can_be_used_directly() {
+    let parsed = day02::Day02.parse(DAY02).unwrap();
+
+    assert_eq!(parsed.games.len(), 5);
+    assert_eq!(day02::Day02.part1(&parsed).unwrap(), Solution::from(8u32));
+}
+
+#[test]
+fn errors_point_at_the_input() {
+    let error = days::get(4).unwrap().solver.run("Card 1: 1 2 | 3\nCard 2: 1 x | 3", None).unwrap_err();
+
+    assert_eq!(error.in_day(4).to_string(), "day04 line 2 column 11: not a number: 'x'");
+}
+
+#[test]
+fn generated_inputs_solve() {
+    for entry in days::DAYS.iter().filter(|entry| entry.solver.is_implemented()) {
+        let input = entry.solver.generate(7, 50)
+            .unwrap_or_else(|| panic!("day {} has no generator", entry.day));
+
+        assert_eq!(entry.solver.generate(7, 50).as_ref(), Some(&input), "day {} is not deterministic", entry.day);
+        assert_ne!(entry.solver.generate(8, 50).as_ref(), Some(&input), "day {} ignores the seed", entry.day);
+        assert!(entry.solver.run(&input, None).is_ok(), "day {} rejects its generated input", entry.day);
+    }
+}
+
+#[test]
+fn references_agree() {
+    for entry in days::DAYS.iter().filter(|entry| entry.solver.is_implemented()) {
+        assert!(entry.solver.has_reference(), "day {} has no reference", entry.day);
+
+        for seed in 0..5 {
+            let input = entry.solver.generate(seed, 40).unwrap();
+            let run = entry.solver.run(&input, None).unwrap();
+            let (part1, part2) = entry.solver.reference(&input, &Params::new())
+                .unwrap_or_else(|| panic!("day {} has no reference", entry.day))
+                .unwrap();
+
+            assert_eq!(run.part1.unwrap().answer, part1, "day {} part 1, seed {}", entry.day, seed);
+            assert_eq!(run.part2.unwrap().answer, part2, "day {} part 2, seed {}", entry.day, seed);
+        }
+    }
+}
+
+#[test]
+fn runtime_params() {
+    let solver = days::get(1).unwrap().solver;
+    let mut params = Params::new();
+    params.set("vocabulary", "# Spanish\nuno = 1\ndos = 2\ndoce = 12\n");
+    params.set("ignore-case", "true");
+
+    let run = solver.run_with("Uno3x\nxDOCE\n7", Some(2), &params).unwrap();
+    assert_eq!(run.part2.unwrap().answer, Solution::from(13u32 + 12 + 77));
+    assert_eq!(solver.reference("Uno3x\nxDOCE\n7", &params).unwrap().unwrap().1, Solution::from(102u32));
+    assert_eq!(solver.run_with("Uno3x\ntwo", None, &params).unwrap_err().to_string(), "line 2: no digit in the line");
+
+    params.set("colour", "red");
+    assert_eq!(solver.run("two", Some(2)).unwrap().part2.unwrap().answer, Solution::from(22u32));
+    assert!(solver.run_with("two", None, &params).is_err());
+    assert!(days::get(2).unwrap().solver.run_with("", None, &params).is_err());
+}